@@ -0,0 +1,177 @@
+//! Allocation counting, installed as the global allocator by the `count-alloc`
+//! feature.
+//!
+//! Counts are kept per thread, so that parts solved side by side don't mix.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+
+/// Whether allocations are counted at all in this build.
+pub const ENABLED: bool = cfg!(feature = "count-alloc");
+
+/// The allocations made while running a function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+    /// The most bytes held at once, over what was held when it started.
+    pub peak: usize,
+}
+
+#[derive(Clone, Copy)]
+struct Counters {
+    count: usize,
+    bytes: usize,
+    // Memory freed by another thread than the one that allocated it can take
+    // this below zero.
+    live: isize,
+    peak: isize,
+}
+
+const ZERO: Counters = Counters {
+    count: 0,
+    bytes: 0,
+    live: 0,
+    peak: 0,
+};
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const { Cell::new(ZERO) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // Fails while the thread is being torn down, when there's nothing left to
+    // report anyway.
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        if allocated > 0 {
+            c.count += 1;
+            c.bytes += allocated;
+        }
+        c.live += allocated as isize - freed as isize;
+        c.peak = c.peak.max(c.live);
+        counters.set(c);
+    });
+}
+
+/// Forwards to the system allocator, counting on the way.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Runs `f` and returns its result with the allocations it made on this thread.
+///
+/// Everything is zero unless the counting allocator is installed.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let outer = COUNTERS.with(|counters| counters.replace(ZERO));
+    let result = f();
+    let inner = COUNTERS.with(|counters| counters.get());
+    COUNTERS.with(|counters| {
+        counters.set(Counters {
+            count: outer.count + inner.count,
+            bytes: outer.bytes + inner.bytes,
+            live: outer.live + inner.live,
+            peak: outer.peak.max(outer.live + inner.peak),
+        })
+    });
+    let allocations = Allocations {
+        count: inner.count,
+        bytes: inner.bytes,
+        peak: inner.peak.max(0) as usize,
+    };
+    (result, allocations)
+}
+
+/// A number of bytes with a unit suited to its magnitude.
+pub struct Bytes(pub usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.0 as f64;
+        let text = if self.0 < 1 << 10 {
+            format!("{}B", self.0)
+        } else if self.0 < 1 << 20 {
+            format!("{:.1}KiB", bytes / 1024.0)
+        } else if self.0 < 1 << 30 {
+            format!("{:.1}MiB", bytes / 1024.0 / 1024.0)
+        } else {
+            format!("{:.1}GiB", bytes / 1024.0 / 1024.0 / 1024.0)
+        };
+        f.pad(&text)
+    }
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.count,
+            Bytes(self.bytes),
+            Bytes(self.peak)
+        )
+    }
+}
+
+#[test]
+fn test_measure() {
+    // Calls the allocator directly, so that the test doesn't depend on it
+    // being installed.
+    let layout = |size| Layout::from_size_align(size, 8).unwrap();
+    let (_, allocations) = measure(|| unsafe {
+        let a = Counting.alloc(layout(100));
+        let (_, inner) = measure(|| {
+            let b = Counting.alloc(layout(50));
+            Counting.dealloc(b, layout(50));
+        });
+        assert_eq!(inner.peak, 50);
+        let a = Counting.realloc(a, layout(100), 200);
+        Counting.dealloc(a, layout(200));
+    });
+    let expected = Allocations {
+        count: 3,
+        bytes: 350,
+        peak: 200,
+    };
+    if ENABLED {
+        // The test harness may allocate on this thread too.
+        assert!(allocations.count >= expected.count);
+    } else {
+        assert_eq!(allocations, expected);
+    }
+    assert_eq!(Bytes(1536).to_string(), "1.5KiB");
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::parse::{self, ParseError};
+use crate::Answer;
+
+/// Known answers to the puzzle inputs, keyed by day and part.
+///
+/// Each line holds a day, a part and the answer, with the rows of grid answers
+/// separated by `\n`. A line starting with `skip` holds a day, a part and the
+/// reason it isn't checked instead. Blank lines and lines starting with `#`
+/// are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(usize, u8), String>,
+    skipped: BTreeMap<(usize, u8), String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail(String),
+    Missing,
+}
+
+fn encode(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+impl Answers {
+    pub fn get(&self, day: usize, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Why a part isn't checked, if it is skipped.
+    pub fn skipped(&self, day: usize, part: u8) -> Option<&str> {
+        self.skipped.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: usize, part: u8, answer: &Answer) {
+        self.answers.insert((day, part), encode(answer));
+    }
+
+    /// Compares an answer with the recorded one, returning the expected answer on failure.
+    pub fn check(&self, day: usize, part: u8, answer: &Answer) -> Check {
+        match self.get(day, part) {
+            Some(expected) if expected == encode(answer) => Check::Pass,
+            Some(expected) => Check::Fail(expected.replace("\\n", "\n")),
+            None => Check::Missing,
+        }
+    }
+
+    /// Parses the answers to a single day, each line holding a part and the
+    /// answer, as in the `.expected` files next to the inputs of `aoc batch`.
+    pub fn parse_day(day: usize, s: &str) -> Result<Self, ParseError> {
+        parse_answers(s, Some(day))
+    }
+}
+
+fn parse_answers(s: &str, day: Option<usize>) -> Result<Answers, ParseError> {
+    let (mut answers, mut skipped) = (BTreeMap::new(), BTreeMap::new());
+    for line in s.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (line, skip) = match line.strip_prefix("skip ") {
+            Some(rest) if day.is_none() => (rest, true),
+            _ => (line, false),
+        };
+        let mut fields = line.splitn(if day.is_some() { 2 } else { 3 }, ' ');
+        let mut field = |expected| match fields.next() {
+            Some(field) if !field.is_empty() => Ok(field),
+            _ => Err(ParseError::end(line, expected).within(s, line)),
+        };
+        let day = match day {
+            Some(day) => day,
+            None => parse::number(s, field("a day")?)?,
+        };
+        let part = field("a part")?;
+        let part = match parse::number(s, part)? {
+            part @ (1 | 2) => part,
+            _ => return Err(ParseError::at(s, part, "part 1 or 2")),
+        };
+        if skip {
+            skipped.insert((day, part), field("a reason")?.to_string());
+        } else {
+            answers.insert((day, part), field("an answer")?.to_string());
+        }
+    }
+    Ok(Answers { answers, skipped })
+}
+
+impl FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_answers(s, None)
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day part answer")?;
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "{} {} {}", day, part, answer)?;
+        }
+        for ((day, part), reason) in &self.skipped {
+            writeln!(f, "skip {} {} {}", day, part, reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let mut answers = Answers::default();
+    answers.insert(
+        13,
+        2,
+        &Answer::Grid(vec!["#.".to_string(), ".#".to_string()]),
+    );
+    answers.insert(1, 1, &Answer::Number(1709));
+    let text = answers.to_string();
+    assert_eq!(text, "# day part answer\n1 1 1709\n13 2 #.\\n.#\n");
+    assert_eq!(text.parse::<Answers>().unwrap(), answers);
+}
+
+#[test]
+fn test_check() {
+    let answers: Answers = "# comment\n\n6 1 362346\n".parse().unwrap();
+    assert_eq!(answers.check(6, 1, &Answer::Number(362346)), Check::Pass);
+    assert_eq!(
+        answers.check(6, 1, &Answer::Number(5)),
+        Check::Fail("362346".to_string())
+    );
+    assert_eq!(answers.check(6, 2, &Answer::Number(5)), Check::Missing);
+    let e = "6 3 1\n".parse::<Answers>().unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (1, 3, "3"));
+}
+
+#[test]
+fn test_skip() {
+    let text = "# day part answer\n6 1 362346\nskip 24 1 takes hours\n";
+    let answers: Answers = text.parse().unwrap();
+    assert_eq!(answers.skipped(24, 1), Some("takes hours"));
+    assert_eq!(answers.skipped(6, 1), None);
+    assert_eq!(answers.check(24, 1, &Answer::Number(5)), Check::Missing);
+    assert_eq!(answers.to_string(), text);
+    let e = "skip 24 1\n".parse::<Answers>().unwrap_err();
+    assert_eq!(e.expected, "a reason");
+}
+
+#[test]
+fn test_parse_day() {
+    let answers = Answers::parse_day(5, "# part answer\n1 5\n2 12\n").unwrap();
+    assert_eq!(answers.check(5, 2, &Answer::Number(12)), Check::Pass);
+    assert_eq!(answers.get(6, 1), None);
+    let e = Answers::parse_day(5, "1 5\n2\n").unwrap_err();
+    assert_eq!((e.line, e.expected.as_str()), (2, "an answer"));
+}
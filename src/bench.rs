@@ -0,0 +1,102 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Wall times of repeated runs of one phase of a solver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timings {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Timings {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort_unstable();
+        Timings {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+
+    /// Bytes of input handled per second, at the median time.
+    pub fn throughput(&self, bytes: usize) -> f64 {
+        bytes as f64 / self.median.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+/// Runs `f` `runs` times and returns the last result with the timings.
+///
+/// Results of earlier runs are dropped outside of the timed section.
+pub fn measure<T>(runs: usize, mut f: impl FnMut() -> T) -> (T, Timings) {
+    assert!(runs > 0, "at least one run is needed");
+    let mut samples = Vec::with_capacity(runs);
+    let mut result = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let output = black_box(f());
+        samples.push(start.elapsed());
+        result = Some(output);
+    }
+    (result.unwrap(), Timings::from_samples(samples))
+}
+
+/// A duration with a unit suited to its magnitude.
+pub struct Elapsed(pub Duration);
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        let text = if nanos < 1_000 {
+            format!("{}ns", nanos)
+        } else if nanos < 1_000_000 {
+            format!("{:.1}µs", nanos as f64 / 1e3)
+        } else if nanos < 1_000_000_000 {
+            format!("{:.2}ms", nanos as f64 / 1e6)
+        } else {
+            format!("{:.2}s", self.0.as_secs_f64())
+        };
+        f.pad(&text)
+    }
+}
+
+/// A rate in bytes per second with a unit suited to its magnitude.
+pub struct Throughput(pub f64);
+
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = if self.0 < 1e3 {
+            format!("{:.0}B/s", self.0)
+        } else if self.0 < 1e6 {
+            format!("{:.1}KB/s", self.0 / 1e3)
+        } else if self.0 < 1e9 {
+            format!("{:.1}MB/s", self.0 / 1e6)
+        } else {
+            format!("{:.1}GB/s", self.0 / 1e9)
+        };
+        f.pad(&text)
+    }
+}
+
+#[test]
+fn test_timings() {
+    let samples = [5, 1, 4, 2, 3].map(Duration::from_millis).to_vec();
+    let timings = Timings::from_samples(samples);
+    assert_eq!(timings.min, Duration::from_millis(1));
+    assert_eq!(timings.median, Duration::from_millis(3));
+    assert_eq!(timings.max, Duration::from_millis(5));
+    assert_eq!(timings.throughput(3000), 1e6);
+}
+
+#[test]
+fn test_units() {
+    assert_eq!(Elapsed(Duration::from_nanos(512)).to_string(), "512ns");
+    assert_eq!(Elapsed(Duration::from_micros(1500)).to_string(), "1.50ms");
+    assert_eq!(
+        format!("{:>8}", Elapsed(Duration::from_secs(2))),
+        "   2.00s"
+    );
+    assert_eq!(Throughput(2.5e6).to_string(), "2.5MB/s");
+}
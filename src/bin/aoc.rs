@@ -0,0 +1,1303 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use advent2021::alloc::{self, Allocations, Bytes};
+use advent2021::answers::{Answers, Check};
+use advent2021::bench::{self, Elapsed, Throughput};
+use advent2021::checked::{self, Overflow};
+use advent2021::fetch::{self, Config};
+use advent2021::meta::Part;
+use advent2021::stepper::{self, Action, Keys, Simulation};
+use advent2021::trace::{self, Level};
+use advent2021::{frames, gen, json, notes, params, Answer, DAYS};
+
+const USAGE: &str = "usage: aoc run <day> [--part 1|2] [--input <path>|-] [--format text|json] [--param <name>=<value>]...
+       aoc bench [<day>...] [--runs <n>]
+       aoc all [--jobs <n>] [--timeout <seconds>]
+       aoc verify [<day>...] [--record] [--answers <path>] [--timeout <seconds>]
+       aoc batch <day> <dir> [--timeout <seconds>]
+       aoc list [<day>...] [--describe]
+       aoc fetch <day> [--base-url <url>] [--config <path>]
+       aoc gen <day> [--size <n>] [--seed <n>]
+       aoc frames <day> [--input <path>|-] [--out <dir>] [--scale <n>] [--palette <rrggbb,...>]
+       aoc step <day> [--input <path>|-] [--delay <ms>]
+
+Every command also takes --trace off|info|debug|trace and --trace-file <path>.";
+
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+struct RunOptions {
+    day: usize,
+    part: Option<u8>,
+    input: Option<String>,
+    format: Format,
+    params: Vec<(String, String)>,
+}
+
+struct BenchOptions {
+    days: Vec<usize>,
+    runs: usize,
+}
+
+struct AllOptions {
+    jobs: usize,
+    timeout: Duration,
+}
+
+struct VerifyOptions {
+    days: Vec<usize>,
+    record: bool,
+    answers: String,
+    timeout: Duration,
+}
+
+struct BatchOptions {
+    day: usize,
+    dir: String,
+    timeout: Duration,
+}
+
+struct ListOptions {
+    days: Vec<usize>,
+    describe: bool,
+}
+
+struct FetchOptions {
+    day: usize,
+    base_url: Option<String>,
+    config: Option<String>,
+}
+
+struct GenOptions {
+    day: usize,
+    size: usize,
+    seed: u64,
+}
+
+struct FramesOptions {
+    day: usize,
+    input: Option<String>,
+    out: Option<String>,
+    scale: usize,
+    palette: Option<Vec<frames::Color>>,
+}
+
+struct StepOptions {
+    day: usize,
+    input: Option<String>,
+    delay: Duration,
+}
+
+fn parse_day(arg: Option<String>) -> Result<usize, String> {
+    arg.and_then(|day| day.parse().ok())
+        .filter(|day| (1..=DAYS.len()).contains(day))
+        .ok_or_else(|| format!("expected a day between 1 and {}\n{}", DAYS.len(), USAGE))
+}
+
+/// Puzzle constants can only be changed for a single run, so that the other
+/// commands always solve the puzzles as they are.
+fn param_unsupported(command: &str) -> String {
+    format!(
+        "--param is only supported by aoc run, aoc {} solves the puzzles as they are\n{}",
+        command, USAGE
+    )
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunOptions, String> {
+    let day = parse_day(args.next())?;
+    let mut options = RunOptions {
+        day,
+        part: None,
+        input: None,
+        format: Format::Text,
+        params: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                let part = args.next().and_then(|part| part.parse().ok());
+                match part {
+                    Some(part @ (1 | 2)) => options.part = Some(part),
+                    _ => return Err(format!("expected part 1 or 2\n{}", USAGE)),
+                }
+            }
+            "--input" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("expected a path\n{}", USAGE))?;
+                options.input = Some(path);
+            }
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    _ => return Err(format!("expected text or json\n{}", USAGE)),
+                };
+            }
+            "--param" => {
+                let param = args
+                    .next()
+                    .ok_or_else(|| format!("expected name=value\n{}", USAGE))?;
+                let param = params::parse_override(DAYS[day - 1].params(), &param)
+                    .map_err(|e| format!("day {}: {}", day, e))?;
+                options.params.push(param);
+            }
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, String> {
+    let mut options = BenchOptions {
+        days: Vec::new(),
+        runs: 10,
+    };
+    while let Some(arg) = args.next() {
+        if arg == "--runs" {
+            options.runs = args
+                .next()
+                .and_then(|runs| runs.parse().ok())
+                .filter(|runs| *runs > 0)
+                .ok_or_else(|| format!("expected a number of runs\n{}", USAGE))?;
+        } else if arg == "--param" {
+            return Err(param_unsupported("bench"));
+        } else {
+            options.days.push(parse_day(Some(arg))?);
+        }
+    }
+    if options.days.is_empty() {
+        options.days = (1..=DAYS.len()).collect();
+    }
+    Ok(options)
+}
+
+fn parse_all(mut args: impl Iterator<Item = String>) -> Result<AllOptions, String> {
+    let mut options = AllOptions {
+        jobs: thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        timeout: Duration::from_secs(60),
+    };
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        match arg.as_str() {
+            "--jobs" => {
+                options.jobs = value
+                    .and_then(|jobs| jobs.parse().ok())
+                    .filter(|jobs| *jobs > 0)
+                    .ok_or_else(|| format!("expected a number of jobs\n{}", USAGE))?;
+            }
+            "--timeout" => {
+                let seconds = value
+                    .and_then(|seconds| seconds.parse().ok())
+                    .ok_or_else(|| format!("expected a number of seconds\n{}", USAGE))?;
+                options.timeout = Duration::from_secs(seconds);
+            }
+            "--param" => return Err(param_unsupported("all")),
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_verify(mut args: impl Iterator<Item = String>) -> Result<VerifyOptions, String> {
+    let mut options = VerifyOptions {
+        days: Vec::new(),
+        record: false,
+        answers: "answers.txt".to_string(),
+        timeout: Duration::from_secs(60),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => options.record = true,
+            "--answers" => {
+                options.answers = args
+                    .next()
+                    .ok_or_else(|| format!("expected a path\n{}", USAGE))?;
+            }
+            "--timeout" => {
+                let seconds = args
+                    .next()
+                    .and_then(|seconds| seconds.parse().ok())
+                    .ok_or_else(|| format!("expected a number of seconds\n{}", USAGE))?;
+                options.timeout = Duration::from_secs(seconds);
+            }
+            "--param" => return Err(param_unsupported("verify")),
+            _ => options.days.push(parse_day(Some(arg))?),
+        }
+    }
+    if options.days.is_empty() {
+        options.days = (1..=DAYS.len()).collect();
+    }
+    Ok(options)
+}
+
+fn parse_batch(mut args: impl Iterator<Item = String>) -> Result<BatchOptions, String> {
+    let day = parse_day(args.next())?;
+    let dir = args
+        .next()
+        .ok_or_else(|| format!("expected a directory of inputs\n{}", USAGE))?;
+    let mut options = BatchOptions {
+        day,
+        dir,
+        timeout: Duration::from_secs(60),
+    };
+    while let Some(arg) = args.next() {
+        if arg == "--timeout" {
+            let seconds = args
+                .next()
+                .and_then(|seconds| seconds.parse().ok())
+                .ok_or_else(|| format!("expected a number of seconds\n{}", USAGE))?;
+            options.timeout = Duration::from_secs(seconds);
+        } else if arg == "--param" {
+            return Err(param_unsupported("batch"));
+        } else {
+            return Err(format!("unexpected argument '{}'\n{}", arg, USAGE));
+        }
+    }
+    Ok(options)
+}
+
+fn parse_list(args: impl Iterator<Item = String>) -> Result<ListOptions, String> {
+    let mut options = ListOptions {
+        days: Vec::new(),
+        describe: false,
+    };
+    for arg in args {
+        if arg == "--describe" {
+            options.describe = true;
+        } else {
+            options.days.push(parse_day(Some(arg))?);
+        }
+    }
+    if options.days.is_empty() {
+        options.days = (1..=DAYS.len()).collect();
+    }
+    Ok(options)
+}
+
+fn parse_fetch(mut args: impl Iterator<Item = String>) -> Result<FetchOptions, String> {
+    let mut options = FetchOptions {
+        day: parse_day(args.next())?,
+        base_url: None,
+        config: None,
+    };
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("expected a value after {}\n{}", arg, USAGE))?;
+        match arg.as_str() {
+            "--base-url" => options.base_url = Some(value),
+            "--config" => options.config = Some(value),
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_gen(mut args: impl Iterator<Item = String>) -> Result<GenOptions, String> {
+    let mut options = GenOptions {
+        day: parse_day(args.next())?,
+        size: 100,
+        seed: 0,
+    };
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        match arg.as_str() {
+            "--size" => {
+                options.size = value
+                    .and_then(|size| size.parse().ok())
+                    .ok_or_else(|| format!("expected a size\n{}", USAGE))?;
+            }
+            "--seed" => {
+                options.seed = value
+                    .and_then(|seed| seed.parse().ok())
+                    .ok_or_else(|| format!("expected a seed\n{}", USAGE))?;
+            }
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_frames(mut args: impl Iterator<Item = String>) -> Result<FramesOptions, String> {
+    let mut options = FramesOptions {
+        day: parse_day(args.next())?,
+        input: None,
+        out: None,
+        scale: 4,
+        palette: None,
+    };
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("expected a value after {}\n{}", arg, USAGE))?;
+        match arg.as_str() {
+            "--input" => options.input = Some(value),
+            "--out" => options.out = Some(value),
+            "--scale" => {
+                options.scale = value
+                    .parse()
+                    .ok()
+                    .filter(|scale| *scale > 0)
+                    .ok_or_else(|| format!("expected a scale\n{}", USAGE))?;
+            }
+            "--palette" => {
+                let palette = frames::parse_palette(&value)
+                    .map_err(|e| format!("can't parse palette: {}", e))?;
+                options.palette = Some(palette);
+            }
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_step(mut args: impl Iterator<Item = String>) -> Result<StepOptions, String> {
+    let mut options = StepOptions {
+        day: parse_day(args.next())?,
+        input: None,
+        delay: Duration::from_millis(100),
+    };
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("expected a value after {}\n{}", arg, USAGE))?;
+        match arg.as_str() {
+            "--input" => options.input = Some(value),
+            "--delay" => {
+                let delay = value
+                    .parse()
+                    .map_err(|_| format!("expected a delay in milliseconds\n{}", USAGE))?;
+                options.delay = Duration::from_millis(delay);
+            }
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+fn read_input(day: usize, path: Option<&str>) -> io::Result<String> {
+    match path {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path),
+        None => fs::read_to_string(format!("inputs/day{}.txt", day)),
+    }
+}
+
+fn run(options: RunOptions) -> Result<(), String> {
+    let input = read_input(options.day, options.input.as_deref())
+        .map_err(|e| format!("can't read input for day {}: {}", options.day, e))?;
+    let solver = DAYS[options.day - 1];
+    let input = checked::catch(|| solver.parse(&input))
+        .map_err(|overflow| overflow.to_string())?
+        .map_err(|e| format!("can't parse input for day {}: {}", options.day, e))?;
+    for part in [1, 2] {
+        if options.part.is_some_and(|p| p != part) {
+            continue;
+        }
+        let ((answer, notes), allocations) = alloc::measure(|| {
+            notes::collect(|| {
+                params::with(&options.params, || {
+                    checked::catch(|| match part {
+                        1 => solver.part1(input.as_ref()),
+                        _ => solver.part2(input.as_ref()),
+                    })
+                })
+            })
+        });
+        let answer = answer.map_err(|overflow| overflow.to_string())?;
+        if options.part == Some(part) && answer == Answer::Unsolved {
+            return Err(format!(
+                "day {} part {} is not implemented",
+                options.day, part
+            ));
+        }
+        let allocations = (alloc::ENABLED && answer != Answer::Unsolved).then_some(allocations);
+        match options.format {
+            Format::Text => {
+                advent2021::print_answer(&answer);
+                if let Some(allocations) = allocations {
+                    println!("{}", allocations);
+                }
+            }
+            Format::Json if answer != Answer::Unsolved => println!(
+                "{}",
+                json::part(options.day, part, &answer, &notes, allocations.as_ref())
+            ),
+            Format::Json => {}
+        }
+    }
+    Ok(())
+}
+
+fn bench(options: BenchOptions) -> Result<(), String> {
+    println!(
+        "{:>3}  {:<6}  {:>9}  {:>9}  {:>9}  {:>10}",
+        "day", "phase", "min", "median", "max", "throughput"
+    );
+    for day in options.days {
+        let input = match read_input(day, None) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("can't read input for day {}: {}", day, e);
+                continue;
+            }
+        };
+        let solver = DAYS[day - 1];
+        let row = |phase: &str, timings: bench::Timings| {
+            println!(
+                "{:>3}  {:<6}  {:>9}  {:>9}  {:>9}  {:>10}",
+                day,
+                phase,
+                Elapsed(timings.min),
+                Elapsed(timings.median),
+                Elapsed(timings.max),
+                Throughput(timings.throughput(input.len()))
+            );
+        };
+        let parsed = isolated(|| bench::measure(options.runs, || solver.parse(&input)));
+        let parsed = match parsed {
+            Ok((Ok(parsed), timings)) => {
+                row("parse", timings);
+                parsed
+            }
+            Ok((Err(e), _)) => {
+                eprintln!("can't parse input for day {}: {}", day, e);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("day {} parse: {}", day, e);
+                continue;
+            }
+        };
+        for part in 1..=2 {
+            let phase = format!("part {}", part);
+            let measured = isolated(|| {
+                bench::measure(options.runs, || match part {
+                    1 => solver.part1(parsed.as_ref()),
+                    _ => solver.part2(parsed.as_ref()),
+                })
+            });
+            match measured {
+                Ok((Answer::Unsolved, _)) => {}
+                Ok((_, timings)) => row(&phase, timings),
+                Err(e) => eprintln!("day {} {}: {}", day, phase, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs one phase of a day, so that a panic or an overflow is reported
+/// without stopping the other days.
+fn isolated<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| checked::catch(f))) {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(overflow)) => Err(overflow.to_string()),
+        Err(_) => Err("panicked".to_string()),
+    }
+}
+
+/// Solves a day on another thread, so that a part that panics or runs past the
+/// deadline can be reported and abandoned.
+fn solve(
+    day: usize,
+    input: String,
+    parts: &[u8],
+    timeout: Duration,
+) -> Vec<(u8, Result<Answer, String>)> {
+    let (sender, receiver) = mpsc::channel();
+    let solved = parts.to_vec();
+    thread::spawn(move || {
+        let solver = DAYS[day - 1];
+        match checked::catch(|| solver.parse(&input)) {
+            Ok(Ok(parsed)) => {
+                for part in solved {
+                    let answer = checked::catch(|| match part {
+                        1 => solver.part1(parsed.as_ref()),
+                        _ => solver.part2(parsed.as_ref()),
+                    });
+                    let _ = sender.send(Ok((part, answer.map_err(|e| e.to_string()))));
+                }
+            }
+            Ok(Err(e)) => {
+                let _ = sender.send(Err(format!("can't parse input: {}", e)));
+            }
+            Err(overflow) => {
+                let _ = sender.send(Err(overflow.to_string()));
+            }
+        }
+    });
+    let deadline = Instant::now() + timeout;
+    let mut results = Vec::new();
+    for i in 0..parts.len() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok(Ok((part, answer))) => results.push((part, answer)),
+            Ok(Err(e)) => return parts.iter().map(|&part| (part, Err(e.clone()))).collect(),
+            Err(RecvTimeoutError::Timeout) => {
+                let e = format!("timed out after {}s", timeout.as_secs());
+                results.extend(parts[i..].iter().map(|&part| (part, Err(e.clone()))));
+                break;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                results.extend(
+                    parts[i..]
+                        .iter()
+                        .map(|&part| (part, Err("panicked".to_string()))),
+                );
+                break;
+            }
+        }
+    }
+    results
+}
+
+enum Status {
+    Ok,
+    Panicked,
+    Overflowed(Overflow),
+    TimedOut,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Ok => "ok",
+            Status::Panicked => "panicked",
+            Status::Overflowed(_) => "overflowed",
+            Status::TimedOut => "timed out",
+            Status::Error => "error",
+        })
+    }
+}
+
+enum Event {
+    Started(usize, u8),
+    Finished(usize, u8, Duration, Allocations, Result<Answer, Status>),
+    Failed(usize, String),
+}
+
+/// The days left to solve, shared by the workers of `aoc all`.
+struct Queue {
+    days: Vec<usize>,
+    next: AtomicUsize,
+}
+
+impl Queue {
+    fn pop(&self) -> Option<usize> {
+        self.days
+            .get(self.next.fetch_add(1, Ordering::Relaxed))
+            .copied()
+    }
+}
+
+/// Solves days from the queue until it's empty, catching panics so that a
+/// broken day doesn't take the worker down with it.
+fn spawn_worker(queue: Arc<Queue>, sender: Sender<Event>) {
+    thread::spawn(move || {
+        while let Some(day) = queue.pop() {
+            let input = match read_input(day, None) {
+                Ok(input) => input,
+                Err(e) => {
+                    let _ = sender.send(Event::Failed(day, format!("can't read input: {}", e)));
+                    continue;
+                }
+            };
+            let solver = DAYS[day - 1];
+            let parse = || checked::catch(|| solver.parse(&input));
+            let parsed = match panic::catch_unwind(AssertUnwindSafe(parse)) {
+                Ok(Ok(Ok(parsed))) => parsed,
+                Ok(Ok(Err(e))) => {
+                    let _ = sender.send(Event::Failed(day, format!("can't parse input: {}", e)));
+                    continue;
+                }
+                Ok(Err(overflow)) => {
+                    let _ = sender.send(Event::Failed(day, overflow.to_string()));
+                    continue;
+                }
+                Err(_) => {
+                    let _ = sender.send(Event::Failed(day, "parser panicked".to_string()));
+                    continue;
+                }
+            };
+            for part in 1..=2 {
+                let _ = sender.send(Event::Started(day, part));
+                let start = Instant::now();
+                let (answer, allocations) = alloc::measure(|| {
+                    panic::catch_unwind(AssertUnwindSafe(|| {
+                        checked::catch(|| match part {
+                            1 => solver.part1(parsed.as_ref()),
+                            _ => solver.part2(parsed.as_ref()),
+                        })
+                    }))
+                });
+                let answer = match answer {
+                    Ok(Ok(answer)) => Ok(answer),
+                    Ok(Err(overflow)) => Err(Status::Overflowed(overflow)),
+                    Err(_) => Err(Status::Panicked),
+                };
+                let elapsed = start.elapsed();
+                let _ = sender.send(Event::Finished(day, part, elapsed, allocations, answer));
+            }
+        }
+    });
+}
+
+/// Solves every day on a pool of workers and prints a table of the results.
+///
+/// A worker whose part timed out is abandoned rather than stopped, since Rust
+/// threads can't be killed. It keeps running, and using a core, until the
+/// part finishes or the process exits after printing the table. This is
+/// intended: running parts in child processes would lose the allocation
+/// counts, which are measured in this process.
+fn all(options: AllOptions) -> Result<(), String> {
+    let queue = Arc::new(Queue {
+        days: (1..=DAYS.len()).collect(),
+        next: AtomicUsize::new(0),
+    });
+    let (sender, receiver) = mpsc::channel();
+    for _ in 0..options.jobs {
+        spawn_worker(queue.clone(), sender.clone());
+    }
+
+    let mut running: HashMap<(usize, u8), Instant> = HashMap::new();
+    let mut results = BTreeMap::new();
+    while results.len() < 2 * queue.days.len() {
+        let now = Instant::now();
+        let wait = running
+            .values()
+            .map(|start| (*start + options.timeout).saturating_duration_since(now))
+            .min()
+            .unwrap_or(options.timeout);
+        match receiver.recv_timeout(wait) {
+            Ok(Event::Started(day, part)) => {
+                if !results.contains_key(&(day, part)) {
+                    running.insert((day, part), Instant::now());
+                }
+            }
+            Ok(Event::Finished(day, part, elapsed, allocations, answer)) => {
+                if running.remove(&(day, part)).is_some() {
+                    let (answer, status) = match answer {
+                        Ok(answer) => (Some(answer), Status::Ok),
+                        Err(status) => (None, status),
+                    };
+                    if let Status::Overflowed(overflow) = &status {
+                        eprintln!("day {} part {}: {}", day, part, overflow);
+                    }
+                    results.insert((day, part), (answer, elapsed, Some(allocations), status));
+                }
+            }
+            Ok(Event::Failed(day, e)) => {
+                eprintln!("day {}: {}", day, e);
+                for part in 1..=2 {
+                    results.insert((day, part), (None, Duration::ZERO, None, Status::Error));
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                let expired = running
+                    .iter()
+                    .filter(|(_, start)| now.duration_since(**start) >= options.timeout)
+                    .map(|(key, _)| *key)
+                    .collect::<Vec<(usize, u8)>>();
+                for (day, part) in expired {
+                    // The stuck worker can't be stopped, so its later parts are
+                    // given up on too and another worker takes its place, with
+                    // the stuck one left running until the process exits.
+                    running.remove(&(day, part));
+                    for part in part..=2 {
+                        let timed_out = (None, options.timeout, None, Status::TimedOut);
+                        results.insert((day, part), timed_out);
+                    }
+                    spawn_worker(queue.clone(), sender.clone());
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => unreachable!("the sender is kept alive"),
+        }
+    }
+
+    if alloc::ENABLED {
+        println!(
+            "{:>3}  {:>4}  {:<20}  {:>9}  {:>9}  {:>9}  {:>9}  status",
+            "day", "part", "answer", "elapsed", "allocs", "bytes", "peak"
+        );
+    } else {
+        println!(
+            "{:>3}  {:>4}  {:<20}  {:>9}  status",
+            "day", "part", "answer", "elapsed"
+        );
+    }
+    let mut grids = Vec::new();
+    let mut failed = 0;
+    for ((day, part), (answer, elapsed, allocations, status)) in &results {
+        let answer = match answer {
+            Some(Answer::Unsolved) => continue,
+            Some(Answer::Grid(rows)) => {
+                grids.push((day, part, rows));
+                "see below".to_string()
+            }
+            Some(answer) => answer.to_string(),
+            None => "-".to_string(),
+        };
+        if !matches!(status, Status::Ok) {
+            failed += 1;
+        }
+        let allocations = match allocations {
+            _ if !alloc::ENABLED => String::new(),
+            Some(a) => format!(
+                "{:>9}  {:>9}  {:>9}  ",
+                a.count,
+                Bytes(a.bytes),
+                Bytes(a.peak)
+            ),
+            None => format!("{:>9}  {:>9}  {:>9}  ", "-", "-", "-"),
+        };
+        println!(
+            "{:>3}  {:>4}  {:<20}  {:>9}  {}{}",
+            day,
+            part,
+            answer,
+            Elapsed(*elapsed),
+            allocations,
+            status
+        );
+    }
+    for (day, part, rows) in grids {
+        println!("\nday {} part {}:\n{}", day, part, rows.join("\n"));
+    }
+    if failed > 0 {
+        return Err(format!("{} parts didn't finish", failed));
+    }
+    Ok(())
+}
+
+fn verify(options: VerifyOptions) -> Result<(), String> {
+    let mut answers = match fs::read_to_string(&options.answers) {
+        Ok(answers) => answers
+            .parse()
+            .map_err(|e| format!("can't parse {}: {}", options.answers, e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Answers::default(),
+        Err(e) => return Err(format!("can't read {}: {}", options.answers, e)),
+    };
+    let (mut passed, mut failed, mut missing, mut skipped) = (0, 0, 0, 0);
+    for day in options.days {
+        let meta = DAYS[day - 1].meta();
+        let skips = [1, 2].map(|part| match meta.parts[usize::from(part) - 1] {
+            Part::Solved(_) => answers.skipped(day, part).map(str::to_string),
+            Part::Missing(_) => Some("not solved yet".to_string()),
+            Part::NoPuzzle => None,
+        });
+        let parts = [1, 2]
+            .into_iter()
+            .filter(|&part: &u8| {
+                let i = usize::from(part) - 1;
+                meta.parts[i] != Part::NoPuzzle && skips[i].is_none()
+            })
+            .collect::<Vec<u8>>();
+        let mut results = match read_input(day, None) {
+            _ if parts.is_empty() => Vec::new(),
+            Ok(input) => solve(day, input, &parts, options.timeout),
+            Err(e) => vec![(parts[0], Err(format!("can't read input: {}", e)))],
+        };
+        for (part, skip) in [1, 2].into_iter().zip(skips) {
+            if let Some(reason) = skip {
+                println!("day {:>2} part {}: skipped, {}", day, part, reason);
+                skipped += 1;
+                continue;
+            }
+            let Some(index) = results.iter().position(|(p, _)| *p == part) else {
+                continue;
+            };
+            let (_, result) = results.remove(index);
+            let answer = match result {
+                Ok(Answer::Unsolved) => continue,
+                Ok(answer) => answer,
+                Err(e) => {
+                    println!("day {:>2} part {}: error, {}", day, part, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            match answers.check(day, part, &answer) {
+                Check::Pass => {
+                    println!("day {:>2} part {}: pass", day, part);
+                    passed += 1;
+                }
+                Check::Fail(expected) if !options.record => {
+                    println!(
+                        "day {:>2} part {}: fail, expected {} but got {}",
+                        day, part, expected, answer
+                    );
+                    failed += 1;
+                }
+                Check::Missing if !options.record => {
+                    println!("day {:>2} part {}: missing, got {}", day, part, answer);
+                    missing += 1;
+                }
+                Check::Fail(_) | Check::Missing => {
+                    println!("day {:>2} part {}: recorded {}", day, part, answer);
+                    answers.insert(day, part, &answer);
+                }
+            }
+        }
+    }
+    if options.record {
+        fs::write(&options.answers, answers.to_string())
+            .map_err(|e| format!("can't write {}: {}", options.answers, e))?;
+    }
+    println!(
+        "{} passed, {} failed, {} missing, {} skipped",
+        passed, failed, missing, skipped
+    );
+    if failed > 0 {
+        return Err(format!("{} answers failed", failed));
+    }
+    Ok(())
+}
+
+/// Solves a day on every file of a directory, checking the answers against
+/// the `.expected` file next to an input when there is one.
+fn batch(options: BatchOptions) -> Result<(), String> {
+    let mut paths = fs::read_dir(&options.dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<PathBuf>>>()
+        })
+        .map_err(|e| format!("can't read {}: {}", options.dir, e))?;
+    paths.retain(|path| path.is_file() && path.extension() != Some("expected".as_ref()));
+    paths.sort();
+
+    let mut rows = Vec::new();
+    let mut grids = Vec::new();
+    let (mut passed, mut failed, mut unchecked) = (0, 0, 0);
+    for path in &paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let mut errors = Vec::new();
+        let expected_path = path.with_extension("expected");
+        let expected = match fs::read_to_string(&expected_path) {
+            Ok(text) => Answers::parse_day(options.day, &text)
+                .map_err(|e| errors.push(format!("can't parse {}: {}", expected_path.display(), e)))
+                .ok(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                errors.push(format!("can't read {}: {}", expected_path.display(), e));
+                None
+            }
+        };
+        let start = Instant::now();
+        let results = match fs::read_to_string(path) {
+            Ok(input) => solve(options.day, input, &[1, 2], options.timeout),
+            Err(e) => vec![(1, Err(format!("can't read input: {}", e)))],
+        };
+        let elapsed = start.elapsed();
+        let mut answers = ["-".to_string(), "-".to_string()];
+        for (part, result) in results {
+            let answer = match result {
+                Ok(Answer::Unsolved) => continue,
+                Ok(answer) => answer,
+                Err(e) => {
+                    if !errors.contains(&e) {
+                        errors.push(e);
+                    }
+                    continue;
+                }
+            };
+            if let Some(Check::Fail(expected)) = expected
+                .as_ref()
+                .map(|expected| expected.check(options.day, part, &answer))
+            {
+                errors.push(format!("part {} expected {}", part, expected));
+            }
+            answers[part as usize - 1] = match answer {
+                Answer::Grid(rows) => {
+                    grids.push((name.to_string(), part, rows));
+                    "see below".to_string()
+                }
+                answer => answer.to_string(),
+            };
+        }
+        if !errors.is_empty() {
+            failed += 1;
+        } else if expected.is_some() {
+            passed += 1;
+        } else {
+            unchecked += 1;
+        }
+        rows.push((name.to_string(), answers, elapsed, errors.join("; ")));
+    }
+
+    let width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0).max(4);
+    println!(
+        "{:<width$}  {:<20}  {:<20}  {:>9}  error",
+        "file",
+        "part 1",
+        "part 2",
+        "time",
+        width = width
+    );
+    for (name, [part1, part2], elapsed, error) in rows {
+        println!(
+            "{:<width$}  {:<20}  {:<20}  {:>9}  {}",
+            name,
+            part1,
+            part2,
+            Elapsed(elapsed),
+            error,
+            width = width
+        );
+    }
+    for (name, part, rows) in grids {
+        println!("\n{} part {}:\n{}", name, part, rows.join("\n"));
+    }
+    println!(
+        "{} files: {} passed, {} failed, {} without .expected",
+        paths.len(),
+        passed,
+        failed,
+        unchecked
+    );
+    if failed > 0 {
+        return Err(format!("{} files failed", failed));
+    }
+    Ok(())
+}
+
+/// Prints what each day is and how far along it is, with what each part
+/// computes when describing.
+fn list(options: ListOptions) -> Result<(), String> {
+    println!(
+        "{:>3}  {:<24}  {:<7}  {:<7}  {:>5}  input",
+        "day", "title", "part 1", "part 2", "tests"
+    );
+    let (mut solved, mut parts, mut tested, mut inputs) = (0, 0, 0, 0);
+    for &day in &options.days {
+        let meta = DAYS[day - 1].meta();
+        let input = Path::new(&format!("inputs/day{}.txt", day)).is_file();
+        println!(
+            "{:>3}  {:<24}  {:<7}  {:<7}  {:>5}  {}",
+            day,
+            meta.title,
+            meta.parts[0],
+            meta.parts[1],
+            meta.tests,
+            if input { "yes" } else { "no" }
+        );
+        if options.describe {
+            for (part, description) in meta.parts.iter().enumerate() {
+                if let Some(description) = description.description() {
+                    println!("{:>3}  part {}: {}", "", part + 1, description);
+                }
+            }
+        }
+        parts += meta.parts.iter().filter(|p| **p != Part::NoPuzzle).count();
+        solved += meta
+            .parts
+            .iter()
+            .filter(|p| matches!(p, Part::Solved(_)))
+            .count();
+        tested += usize::from(meta.tests > 0);
+        inputs += usize::from(input);
+    }
+    println!(
+        "{} of {} parts solved, {} of {} days with tests, {} with inputs",
+        solved,
+        parts,
+        tested,
+        options.days.len(),
+        inputs
+    );
+    Ok(())
+}
+
+/// Reads the config file given on the command line, or else the one named by
+/// `AOC_CONFIG`, or else `~/.config/advent2021/config` if it exists.
+fn read_config(path: Option<String>) -> Result<Config, String> {
+    let (path, required) = match path.or_else(|| env::var("AOC_CONFIG").ok()) {
+        Some(path) => (path, true),
+        None => match env::var("HOME") {
+            Ok(home) => (format!("{}/.config/advent2021/config", home), false),
+            Err(_) => return Ok(Config::default()),
+        },
+    };
+    match fs::read_to_string(&path) {
+        Ok(config) => config
+            .parse()
+            .map_err(|e| format!("can't parse {}: {}", path, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(Config::default()),
+        Err(e) => Err(format!("can't read {}: {}", path, e)),
+    }
+}
+
+fn fetch_input(options: FetchOptions) -> Result<(), String> {
+    let path = format!("inputs/day{}.txt", options.day);
+    if fs::metadata(&path).is_ok() {
+        println!("{} is already cached", path);
+        return Ok(());
+    }
+    let config = read_config(options.config)?;
+    let session = env::var("AOC_SESSION")
+        .ok()
+        .or(config.session)
+        .ok_or("no session token, set AOC_SESSION or add 'session = ...' to the config file")?;
+    let base_url = options
+        .base_url
+        .or_else(|| env::var("AOC_BASE_URL").ok())
+        .or(config.base_url)
+        .unwrap_or_else(|| fetch::DEFAULT_BASE_URL.to_string());
+    let input = fetch::get(&fetch::input_url(&base_url, options.day), session.trim())
+        .map_err(|e| format!("can't fetch input for day {}: {}", options.day, e))?;
+    fs::create_dir_all("inputs").map_err(|e| format!("can't create inputs: {}", e))?;
+    fs::write(&path, input).map_err(|e| format!("can't write {}: {}", path, e))?;
+    println!("wrote {}", path);
+    Ok(())
+}
+
+fn generate(options: GenOptions) -> Result<(), String> {
+    let input = gen::generate(options.day, options.size, options.seed)
+        .ok_or_else(|| format!("no generator for day {}", options.day))?;
+    print!("{}", input);
+    Ok(())
+}
+
+/// Switches the terminal to and from handing over each key as it's pressed,
+/// without echoing it, returning whether it worked.
+fn raw_mode(raw: bool) -> bool {
+    let Ok(tty) = fs::File::open("/dev/tty") else {
+        return false;
+    };
+    let args: &[&str] = if raw {
+        &["-icanon", "-echo", "-isig", "min", "1"]
+    } else {
+        &["icanon", "echo", "isig"]
+    };
+    Command::new("stty")
+        .args(args)
+        .stdin(tty)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Sends the keys pressed, read from the terminal, or from the standard input
+/// when there's no terminal.
+fn spawn_key_reader(sender: Sender<u8>) {
+    thread::spawn(move || {
+        let mut keys: Box<dyn Read> = match fs::File::open("/dev/tty") {
+            Ok(tty) => Box::new(tty),
+            Err(_) => Box::new(io::stdin()),
+        };
+        let mut buffer = [0; 16];
+        while let Ok(n @ 1..) = keys.read(&mut buffer) {
+            if buffer[..n].iter().any(|key| sender.send(*key).is_err()) {
+                return;
+            }
+        }
+    });
+}
+
+fn step(options: StepOptions) -> Result<(), String> {
+    let start = stepper::simulation(options.day)
+        .ok_or_else(|| format!("day {} has no simulation to step through", options.day))?;
+    let input = read_input(options.day, options.input.as_deref())
+        .map_err(|e| format!("can't read input for day {}: {}", options.day, e))?;
+    let mut simulation =
+        start(&input).map_err(|e| format!("can't parse input for day {}: {}", options.day, e))?;
+
+    let (sender, receiver) = mpsc::channel();
+    spawn_key_reader(sender);
+    let raw = raw_mode(true);
+    print!("\x1b[?25l");
+    let result = step_through(options.day, simulation.as_mut(), &receiver, options.delay);
+    print!("\x1b[?25h\r\n");
+    if raw {
+        raw_mode(false);
+    }
+    result
+}
+
+fn step_through(
+    day: usize,
+    simulation: &mut dyn Simulation,
+    receiver: &mpsc::Receiver<u8>,
+    delay: Duration,
+) -> Result<(), String> {
+    let mut keys = Keys::default();
+    let mut playing = false;
+    let mut finished = false;
+    let mut stdout = io::stdout();
+    loop {
+        let status = if finished {
+            "finished".to_string()
+        } else if playing {
+            "playing".to_string()
+        } else if !keys.pending().is_empty() {
+            format!("steps: {}", keys.pending())
+        } else {
+            String::new()
+        };
+        write!(stdout, "{}", stepper::screen(day, simulation, &status))
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("can't write to the terminal: {}", e))?;
+
+        // While playing, a step is taken whenever no key comes in time.
+        let key = if playing {
+            match receiver.recv_timeout(delay) {
+                Ok(key) => Some(key),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        } else {
+            match receiver.recv() {
+                Ok(key) => Some(key),
+                Err(_) => return Ok(()),
+            }
+        };
+        let steps = match key.map(|key| keys.press(key)) {
+            None => 1,
+            Some(None) => 0,
+            Some(Some(Action::Step(steps))) => steps,
+            Some(Some(Action::TogglePlay)) => {
+                playing = !playing && !finished;
+                0
+            }
+            Some(Some(Action::Quit)) => return Ok(()),
+        };
+        for _ in 0..steps {
+            if finished || !simulation.step() {
+                finished = true;
+                playing = false;
+                break;
+            }
+        }
+    }
+}
+
+fn export_frames(options: FramesOptions) -> Result<(), String> {
+    let animation = frames::animation(options.day)
+        .ok_or_else(|| format!("day {} has no frames to export", options.day))?;
+    let input = read_input(options.day, options.input.as_deref())
+        .map_err(|e| format!("can't read input for day {}: {}", options.day, e))?;
+    let out = options
+        .out
+        .unwrap_or_else(|| format!("frames/day{}", options.day));
+    fs::create_dir_all(&out).map_err(|e| format!("can't create {}: {}", out, e))?;
+    let palette = options.palette.as_deref().unwrap_or(animation.palette);
+    let mut count = 0;
+    let mut error = None;
+    (animation.render)(&input, &mut |frame| {
+        if error.is_some() {
+            return;
+        }
+        let path = format!("{}/frame{:04}.{}", out, count, frames::extension(palette));
+        let written = fs::File::create(&path).and_then(|file| {
+            let mut file = BufWriter::new(file);
+            frames::write_image(&mut file, frame, palette, options.scale)?;
+            file.flush()
+        });
+        match written {
+            Ok(()) => count += 1,
+            Err(e) => error = Some(format!("can't write {}: {}", path, e)),
+        }
+    })
+    .map_err(|e| format!("can't parse input for day {}: {}", options.day, e))?;
+    if let Some(e) = error {
+        return Err(e);
+    }
+    println!("wrote {} frames to {}", count, out);
+    Ok(())
+}
+
+/// Starts tracing as asked by `--trace` and `--trace-file`, wherever they are,
+/// and returns the other arguments.
+fn start_tracing(args: impl Iterator<Item = String>) -> Result<Vec<String>, String> {
+    let mut level = None;
+    let mut path = None;
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => {
+                let value = args.next().unwrap_or_default();
+                level = Some(
+                    value
+                        .parse::<Level>()
+                        .map_err(|e| format!("{}\n{}", e, USAGE))?,
+                );
+            }
+            "--trace-file" => {
+                path = Some(
+                    args.next()
+                        .ok_or_else(|| format!("expected a path\n{}", USAGE))?,
+                );
+            }
+            _ => rest.push(arg),
+        }
+    }
+    // A trace file on its own asks for the least verbose events.
+    let level = level.unwrap_or(if path.is_some() {
+        Level::Info
+    } else {
+        Level::Off
+    });
+    if level != Level::Off {
+        match path {
+            Some(path) => {
+                let file =
+                    fs::File::create(&path).map_err(|e| format!("can't create {}: {}", path, e))?;
+                trace::init(level, Box::new(file));
+            }
+            None => trace::init_stderr(level),
+        }
+    }
+    Ok(rest)
+}
+
+fn main() {
+    let args = match start_tracing(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    let mut args = args.into_iter();
+    let result = match args.next().as_deref() {
+        Some("run") => parse_run(args).and_then(run),
+        Some("bench") => parse_bench(args).and_then(bench),
+        Some("all") => parse_all(args).and_then(all),
+        Some("verify") => parse_verify(args).and_then(verify),
+        Some("batch") => parse_batch(args).and_then(batch),
+        Some("list") => parse_list(args).and_then(list),
+        Some("fetch") => parse_fetch(args).and_then(fetch_input),
+        Some("gen") => parse_gen(args).and_then(generate),
+        Some("frames") => parse_frames(args).and_then(export_frames),
+        Some("step") => parse_step(args).and_then(step),
+        _ => Err(USAGE.to_string()),
+    };
+    trace::flush();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(2);
+    }
+}
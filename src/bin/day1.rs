@@ -0,0 +1,6 @@
+use advent2021::day1::Day1;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day1, include_str!("../../inputs/day1.txt"))
+}
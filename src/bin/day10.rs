@@ -0,0 +1,6 @@
+use advent2021::day10::Day10;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day10, include_str!("../../inputs/day10.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day11::Day11;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day11, include_str!("../../inputs/day11.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day12::Day12;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day12, include_str!("../../inputs/day12.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day13::Day13;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day13, include_str!("../../inputs/day13.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day14::Day14;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day14, include_str!("../../inputs/day14.txt"))
+}
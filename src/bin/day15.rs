@@ -0,0 +1,6 @@
+use advent2021::day15::Day15;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day15, include_str!("../../inputs/day15.txt"))
+}
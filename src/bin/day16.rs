@@ -0,0 +1,6 @@
+use advent2021::day16::Day16;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day16, include_str!("../../inputs/day16.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day17::Day17;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day17, include_str!("../../inputs/day17.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day18::Day18;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day18, include_str!("../../inputs/day18.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day19::Day19;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day19, include_str!("../../inputs/day19.txt"))
+}
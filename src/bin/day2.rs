@@ -0,0 +1,6 @@
+use advent2021::day2::Day2;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day2, include_str!("../../inputs/day2.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day20::Day20;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day20, include_str!("../../inputs/day20.txt"))
+}
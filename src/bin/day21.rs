@@ -0,0 +1,6 @@
+use advent2021::day21::Day21;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day21, include_str!("../../inputs/day21.txt"))
+}
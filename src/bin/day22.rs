@@ -0,0 +1,6 @@
+use advent2021::day22::Day22;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day22, include_str!("../../inputs/day22.txt"))
+}
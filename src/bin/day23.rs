@@ -0,0 +1,6 @@
+use advent2021::day23::Day23;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day23, include_str!("../../inputs/day23.txt"))
+}
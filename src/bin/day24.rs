@@ -0,0 +1,6 @@
+use advent2021::day24::Day24;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day24, include_str!("../../inputs/day24.txt"))
+}
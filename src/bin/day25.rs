@@ -0,0 +1,6 @@
+use advent2021::day25::Day25;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day25, include_str!("../../inputs/day25.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day3::Day3;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day3, include_str!("../../inputs/day3.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day4::Day4;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day4, include_str!("../../inputs/day4.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day5::Day5;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day5, include_str!("../../inputs/day5.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day6::Day6;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day6, include_str!("../../inputs/day6.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day7::Day7;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day7, include_str!("../../inputs/day7.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day8::Day8;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day8, include_str!("../../inputs/day8.txt"))
+}
@@ -0,0 +1,6 @@
+use advent2021::day9::Day9;
+use advent2021::parse::ParseError;
+
+fn main() -> Result<(), ParseError> {
+    advent2021::run(&Day9, include_str!("../../inputs/day9.txt"))
+}
@@ -0,0 +1,192 @@
+//! Arithmetic that reports overflow instead of wrapping, built with the
+//! `checked-arithmetic` feature.
+//!
+//! The helpers panic with an [`Overflow`] naming the day they were called
+//! from, which [`catch`] turns back into an error. Without the feature they
+//! do what the plain operators and `as` casts would.
+
+use std::fmt;
+use std::ops::{Add, Mul, Shl, Sub};
+use std::panic::{self, AssertUnwindSafe, Location};
+use std::path::Path;
+use std::sync::Once;
+
+/// Whether overflow is checked at all in this build.
+pub const ENABLED: bool = cfg!(feature = "checked-arithmetic");
+
+/// An operation whose result doesn't fit in its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// The module the operation is in, such as `day2`.
+    pub day: &'static str,
+    pub op: &'static str,
+    pub operands: (String, String),
+    /// The type of the result.
+    pub ty: &'static str,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = &self.operands;
+        match self.op {
+            "as" => write!(f, "{}: {} as {} is out of range", self.day, a, b),
+            op => write!(f, "{}: {} {} {} overflows {}", self.day, a, op, b, self.ty),
+        }
+    }
+}
+
+pub trait Integer:
+    Copy
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Shl<u32, Output = Self>
+{
+    const NAME: &'static str;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    /// Shifts left, unless bits that were set would be shifted out.
+    fn checked_shl(self, bits: u32) -> Option<Self>;
+    fn to_i128(self) -> i128;
+    fn try_from_i128(n: i128) -> Option<Self>;
+    /// The same as an `as` cast from the original type.
+    fn wrapping_from_i128(n: i128) -> Self;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const NAME: &'static str = stringify!($t);
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn checked_shl(self, bits: u32) -> Option<Self> {
+                <$t>::checked_shl(self, bits).filter(|n| n >> bits == self)
+            }
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn try_from_i128(n: i128) -> Option<Self> {
+                Self::try_from(n).ok()
+            }
+
+            fn wrapping_from_i128(n: i128) -> Self {
+                n as Self
+            }
+        })*
+    };
+}
+
+integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+#[track_caller]
+fn overflow(op: &'static str, a: impl fmt::Display, b: impl fmt::Display, ty: &'static str) -> ! {
+    let file = Location::caller().file();
+    let day = Path::new(file)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file);
+    panic::panic_any(Overflow {
+        day,
+        op,
+        operands: (a.to_string(), b.to_string()),
+        ty,
+    })
+}
+
+#[track_caller]
+pub fn add<T: Integer>(a: T, b: T) -> T {
+    match a.checked_add(b) {
+        None if ENABLED => overflow("+", a, b, T::NAME),
+        _ => a + b,
+    }
+}
+
+#[track_caller]
+pub fn sub<T: Integer>(a: T, b: T) -> T {
+    match a.checked_sub(b) {
+        None if ENABLED => overflow("-", a, b, T::NAME),
+        _ => a - b,
+    }
+}
+
+#[track_caller]
+pub fn mul<T: Integer>(a: T, b: T) -> T {
+    match a.checked_mul(b) {
+        None if ENABLED => overflow("*", a, b, T::NAME),
+        _ => a * b,
+    }
+}
+
+#[track_caller]
+pub fn shl<T: Integer>(a: T, bits: u32) -> T {
+    match a.checked_shl(bits) {
+        None if ENABLED => overflow("<<", a, bits, T::NAME),
+        _ => a << bits,
+    }
+}
+
+/// Converts between integer types, like an `as` cast.
+#[track_caller]
+pub fn cast<T: Integer, U: Integer>(n: T) -> U {
+    match U::try_from_i128(n.to_i128()) {
+        None if ENABLED => overflow("as", n, U::NAME, U::NAME),
+        _ => U::wrapping_from_i128(n.to_i128()),
+    }
+}
+
+/// Runs `f`, returning the overflow it ran into, if any. Other panics are
+/// left to unwind.
+///
+/// Overflows are left out of the panic messages from then on, since they are
+/// reported where they are caught.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Overflow> {
+    static QUIET: Once = Once::new();
+    QUIET.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !info.payload().is::<Overflow>() {
+                hook(info);
+            }
+        }));
+    });
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast() {
+        Ok(overflow) => *overflow,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+#[test]
+fn test_checked() {
+    assert_eq!(add(2_u8, 3), 5);
+    assert_eq!(shl(0xf_u64, 60), 0xf << 60);
+    assert_eq!(cast::<i64, u32>(7), 7);
+    assert_eq!(catch(|| sub(5_usize, 3)), Ok(2));
+    if ENABLED {
+        let overflow = catch(|| sub(3_usize, 5)).unwrap_err();
+        assert_eq!(overflow.day, "checked");
+        assert_eq!(overflow.to_string(), "checked: 3 - 5 overflows usize");
+        assert_eq!(
+            catch(|| shl(0x1f_u64, 60)).unwrap_err().to_string(),
+            "checked: 31 << 60 overflows u64"
+        );
+        assert_eq!(
+            catch(|| cast::<i32, u32>(-3)).unwrap_err().to_string(),
+            "checked: -3 as u32 is out of range"
+        );
+    }
+}
@@ -1,19 +1,58 @@
-fn increases(measurements: &[u64]) -> usize {
+use crate::meta::{Meta, Part};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+/// Counts the measurements larger than the one before.
+pub fn increases(measurements: &[u64]) -> usize {
     measurements.windows(2).filter(|x| x[1] > x[0]).count()
 }
 
-fn convolve(measurements: &[u64]) -> Vec<u64> {
+/// Sums of each three-measurement sliding window.
+pub fn convolve(measurements: &[u64]) -> Vec<u64> {
     measurements.windows(3).map(|x| x.iter().sum()).collect()
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day1.txt");
-    let measurements = INPUT
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<u64>>();
-    println!("increased {} times", increases(&measurements));
-    println!("increased {} times", increases(&convolve(&measurements)));
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut depth = 100;
+    (0..size)
+        .map(|_| {
+            depth = (depth + rng.range(-20..40)).max(0);
+            format!("{}\n", depth)
+        })
+        .collect()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u64>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Sonar Sweep",
+        parts: [
+            Part::Solved("the number of depth measurements larger than the previous one"),
+            Part::Solved("the same for sums of three measurements in a sliding window"),
+        ],
+        tests: 2,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| parse::number(input, line))
+            .collect()
+    }
+
+    fn part1(measurements: &Self::Input) -> usize {
+        increases(measurements)
+    }
+
+    fn part2(measurements: &Self::Input) -> usize {
+        increases(&convolve(measurements))
+    }
 }
 
 #[test]
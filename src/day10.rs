@@ -1,3 +1,8 @@
+use crate::meta::{Meta, Part};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::{Answer, Solution};
+
 struct Tokenizer {
     stack: Option<Vec<char>>,
     corrupted: Option<char>,
@@ -67,41 +72,104 @@ impl Tokenizer {
     }
 }
 
-fn first_illegal_character(line: &str) -> Option<char> {
+/// The first closing character that doesn't match, if the line is corrupted.
+pub fn first_illegal_character(line: &str) -> Option<char> {
     let tokenizer = Tokenizer::tokenize(line);
     tokenizer.corrupted
 }
 
-fn completion_score(line: &str) -> Option<u64> {
+/// The score of the characters that complete the line, if it's incomplete.
+pub fn completion_score(line: &str) -> Option<u64> {
     let tokenizer = Tokenizer::tokenize(line);
     tokenizer.score()
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day10.txt");
-
-    let score = INPUT
-        .lines()
-        .map(|line| {
-            let c = first_illegal_character(line);
-            match c {
-                Some(')') => 3,
-                Some(']') => 57,
-                Some('}') => 1197,
-                Some('>') => 25137,
-                _ => 0,
+const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// A mix of corrupted and incomplete lines, starting with an incomplete one,
+/// with at most 20 chunks open so that completion scores fit in 64 bits.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|i| {
+            let mut line = String::new();
+            let mut stack = Vec::new();
+            for _ in 0..rng.range(10..100) {
+                if stack.is_empty() || (stack.len() < 20 && rng.chance(3, 5)) {
+                    let (open, close) = *rng.choose(&PAIRS);
+                    line.push(open);
+                    stack.push(close);
+                } else {
+                    line.extend(stack.pop());
+                }
             }
+            if i > 0 && rng.chance(1, 2) {
+                let wrong = PAIRS
+                    .iter()
+                    .map(|(_, close)| *close)
+                    .filter(|close| stack.last() != Some(close))
+                    .collect::<Vec<char>>();
+                line.push(*rng.choose(&wrong));
+            } else if stack.is_empty() {
+                line.push(rng.choose(&PAIRS).0);
+            }
+            line + "\n"
         })
-        .sum::<u32>();
-    println!("solution {}", score);
-
-    let mut scores = INPUT
-        .lines()
-        .filter_map(completion_score)
-        .collect::<Vec<u64>>();
-    scores.sort_unstable();
-    let score = scores[(scores.len() - 1) / 2];
-    println!("solution {}", score);
+        .collect()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Syntax Scoring",
+        parts: [
+            Part::Solved("the syntax error score of the corrupted lines"),
+            Part::Solved("the middle completion score of the incomplete lines"),
+        ],
+        tests: 2,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| match line.find(|c| !"()[]{}<>".contains(c)) {
+                Some(i) => Err(ParseError::at(input, &line[i..], "a bracket")),
+                None => Ok(line.to_string()),
+            })
+            .collect()
+    }
+
+    fn part1(lines: &Self::Input) -> u32 {
+        lines
+            .iter()
+            .map(|line| {
+                let c = first_illegal_character(line);
+                match c {
+                    Some(')') => 3,
+                    Some(']') => 57,
+                    Some('}') => 1197,
+                    Some('>') => 25137,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    fn part2(lines: &Self::Input) -> Answer {
+        let mut scores = lines
+            .iter()
+            .filter_map(|line| completion_score(line))
+            .collect::<Vec<u64>>();
+        if scores.is_empty() {
+            return Answer::Text("no incomplete lines".to_string());
+        }
+        scores.sort_unstable();
+        scores[(scores.len() - 1) / 2].into()
+    }
 }
 
 #[test]
@@ -111,3 +179,17 @@ fn test_completion_score() {
     assert_eq!(completion_score("{([(<{}[<>[]}>{[]{[(<()>"), None);
     assert_eq!(completion_score("<{([{{}}[<[[[<>{}]]]>[]]"), Some(294));
 }
+
+#[test]
+fn test_no_incomplete_lines() {
+    let lines = Day10::parse("{([(<{}[<>[]}>{[]{[(<()>\n").unwrap();
+    assert_eq!(Day10::part1(&lines), 1197);
+    assert_eq!(
+        Day10::part2(&lines),
+        Answer::Text("no incomplete lines".to_string())
+    );
+    assert_eq!(
+        Day10::part2(&Day10::parse("").unwrap()),
+        Answer::Text("no incomplete lines".to_string())
+    );
+}
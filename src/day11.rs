@@ -1,114 +1,290 @@
 use std::collections::HashSet;
 
-fn square(x: usize, y: usize) -> Vec<(usize, usize)> {
-    let mut sq = Vec::new();
-    if y > 0 {
-        if x > 0 {
-            sq.push((x - 1, y - 1));
-        }
-        sq.push((x, y - 1));
-        if x < 9 {
-            sq.push((x + 1, y - 1));
-        }
-    }
-    if x > 0 {
-        sq.push((x - 1, y));
-    }
-    if x < 9 {
-        sq.push((x + 1, y));
-    }
-    if y < 9 {
-        if x > 0 {
-            sq.push((x - 1, y + 1));
-        }
-        sq.push((x, y + 1));
-        if x < 9 {
-            sq.push((x + 1, y + 1));
-        }
-    }
-    sq
+use crate::differential::{self, Outcome};
+use crate::event;
+use crate::frames::Color;
+use crate::grid::Grid;
+use crate::meta::{Meta, Part};
+use crate::params::{Param, Tunable};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::stepper::Simulation;
+use crate::{Answer, Solution};
+
+fn flashed_neighbors(
+    grid: &Grid<u32>,
+    flashed: &HashSet<(usize, usize)>,
+    x: usize,
+    y: usize,
+) -> u32 {
+    grid.neighbors8(x, y)
+        .filter(|position| flashed.contains(position))
+        .count() as u32
 }
 
-fn step(grid: &mut Vec<Vec<u32>>) -> usize {
+/// Advances the octopuses by one step, returning how many flashed.
+pub fn step(grid: &mut Grid<u32>) -> usize {
     let mut flashed = HashSet::new();
 
-    loop {
-        let mut new_flashes = false;
-        for (y, row) in grid.iter().enumerate() {
-            for (x, octopus) in row.iter().enumerate() {
-                let adjacent = square(x, y)
-                    .iter()
-                    .filter(|(xx, yy)| flashed.contains(&(*xx, *yy)))
-                    .count() as u32;
-                if (octopus + adjacent + 1) >= 10 && !flashed.contains(&(x, y)) {
-                    flashed.insert((x, y));
-                    new_flashes = true;
-                }
+    // Each wave of the cascade flashes the octopuses that the previous waves
+    // charged up enough.
+    for wave in 1.. {
+        let mut new_flashes = 0;
+        for (x, y) in grid.positions() {
+            let adjacent = flashed_neighbors(grid, &flashed, x, y);
+            if (grid[(x, y)] + adjacent + 1) >= 10 && !flashed.contains(&(x, y)) {
+                flashed.insert((x, y));
+                new_flashes += 1;
+                event!(Trace, "flash", wave = wave, x = x, y = y);
             }
         }
-        if !new_flashes {
+        if new_flashes == 0 {
+            event!(Debug, "step", flashes = flashed.len(), waves = wave - 1);
             break;
         }
     }
 
-    for (y, row) in grid.iter_mut().enumerate() {
-        for (x, octopus) in row.iter_mut().enumerate() {
-            *octopus = if flashed.contains(&(x, y)) {
+    let octopuses = grid
+        .positions()
+        .map(|(x, y)| {
+            if flashed.contains(&(x, y)) {
                 0
             } else {
-                *octopus
-                    + square(x, y)
-                        .iter()
-                        .filter(|(xx, yy)| flashed.contains(&(*xx, *yy)))
-                        .count() as u32
-                    + 1
-            };
+                grid[(x, y)] + flashed_neighbors(grid, &flashed, x, y) + 1
+            }
+        })
+        .collect();
+    *grid = Grid::from_vec(grid.width(), octopuses);
+
+    flashed.len()
+}
+
+/// Advances the octopuses by one step the way the puzzle tells it, flashing
+/// them one at a time, as a reference for [`step`].
+pub fn step_reference(grid: &mut Grid<u32>) -> usize {
+    for position in grid.positions().collect::<Vec<_>>() {
+        grid[position] += 1;
+    }
+    let mut flashed = HashSet::new();
+    while let Some((x, y)) = grid
+        .positions()
+        .find(|position| grid[*position] > 9 && !flashed.contains(position))
+    {
+        flashed.insert((x, y));
+        for neighbor in grid.neighbors8(x, y).collect::<Vec<_>>() {
+            grid[neighbor] += 1;
         }
     }
-
+    for position in &flashed {
+        grid[*position] = 0;
+    }
     flashed.len()
 }
 
-fn total_flashes(input: &str) -> usize {
-    let mut octopuses = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| char::to_digit(c, 10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect::<Vec<Vec<u32>>>();
+pub fn differential(input: &str) -> Outcome {
+    let Ok(octopuses) = Day11::parse(input) else {
+        return Outcome::Invalid;
+    };
+    let steps = |step: fn(&mut Grid<u32>) -> usize| {
+        let mut octopuses = octopuses.clone();
+        (0..10)
+            .map(|_| (step(&mut octopuses), octopuses.to_string()))
+            .collect::<Vec<(usize, String)>>()
+    };
+    differential::compare(steps(step), steps(step_reference))
+}
 
-    (0..100).fold(0, |acc, _| acc + step(&mut octopuses))
+/// The flashes over the first `steps` steps.
+pub fn total_flashes(octopuses: &Grid<u32>, steps: usize) -> usize {
+    let mut octopuses = octopuses.clone();
+    let flashes = (0..steps).fold(0, |acc, _| acc + step(&mut octopuses));
+    event!(Info, "flashed", steps = steps, flashes = flashes);
+    flashes
 }
 
-fn first_simultaneous_step(input: &str) -> usize {
-    let mut octopuses = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| char::to_digit(c, 10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect::<Vec<Vec<u32>>>();
+/// The first step where every octopus flashes, if it is at most `max_steps`.
+pub fn first_simultaneous_step(octopuses: &Grid<u32>, max_steps: usize) -> Option<usize> {
+    let mut octopuses = octopuses.clone();
+    let size = octopuses.width() * octopuses.height();
+    let Some(s) = (1..=max_steps).find(|_| step(&mut octopuses) == size) else {
+        event!(Info, "unsynchronized", steps = max_steps, octopuses = size);
+        return None;
+    };
+    event!(Info, "synchronized", step = s, octopuses = size);
+    Some(s)
+}
 
-    let mut s = 0;
+/// Flashing octopuses in white, the others in blues that brighten with energy.
+pub const PALETTE: [Color; 10] = [
+    [255, 255, 255],
+    [8, 12, 40],
+    [14, 22, 64],
+    [20, 32, 88],
+    [26, 42, 112],
+    [32, 52, 136],
+    [38, 62, 160],
+    [44, 72, 184],
+    [50, 82, 208],
+    [56, 92, 232],
+];
+
+/// The energy levels at each step, until all the octopuses flash at once or
+/// `max_steps` have been drawn.
+pub fn frames(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError> {
+    let mut octopuses = Day11::parse(input)?;
+    let size = octopuses.width() * octopuses.height();
+    let max_steps = MAX_STEPS.get();
+    frame(&octopuses.map(|energy| *energy as u8));
+    for _ in 0..max_steps {
+        let flashes = step(&mut octopuses);
+        frame(&octopuses.map(|energy| *energy as u8));
+        if flashes == size {
+            return Ok(());
+        }
+    }
+    event!(Info, "unsynchronized", steps = max_steps, octopuses = size);
+    Ok(())
+}
+
+struct Octopuses {
+    grid: Grid<u32>,
+    step: usize,
+    flashes: usize,
+    total: usize,
+}
+
+impl Simulation for Octopuses {
+    fn step(&mut self) -> bool {
+        self.step += 1;
+        self.flashes = step(&mut self.grid);
+        self.total += self.flashes;
+        true
+    }
+
+    fn counters(&self) -> Vec<(&'static str, Answer)> {
+        vec![
+            ("step", self.step.into()),
+            ("flashes", self.flashes.into()),
+            ("total flashes", self.total.into()),
+        ]
+    }
+
+    fn draw(&self) -> String {
+        self.grid.to_string()
+    }
+}
+
+pub fn simulation(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Octopuses {
+        grid: Day11::parse(input)?,
+        step: 0,
+        flashes: 0,
+        total: 0,
+    }))
+}
+
+/// Picks grids again until the octopuses flash all at once, which can take a
+/// while for large grids.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(1);
     loop {
-        s += 1;
-        if step(&mut octopuses) == 100 {
-            break;
+        let cells = (0..size * size).map(|_| rng.below(10) as u32).collect();
+        let grid = Grid::from_vec(size, cells);
+        let mut octopuses = grid.clone();
+        if (0..100 * size + 1000).any(|_| step(&mut octopuses) == size * size) {
+            return grid.to_string();
         }
     }
-    s
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day11.txt");
-    println!("solution {}", total_flashes(INPUT));
-    println!("solution {}", first_simultaneous_step(INPUT));
+/// Any energy levels, whether they ever synchronize or not.
+pub fn generate_small(size: usize, rng: &mut Rng) -> String {
+    let cells = (0..size * size).map(|_| rng.below(10) as u32).collect();
+    Grid::from_vec(size, cells).to_string()
+}
+
+const STEPS: Param<usize> = Param::new("steps", "100", "steps counted in part 1");
+const MAX_STEPS: Param<usize> = Param::new(
+    "max_steps",
+    "10000",
+    "steps tried in part 2 before giving up",
+);
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<u32>;
+    type Part1 = usize;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Dumbo Octopus",
+        parts: [
+            Part::Solved("the number of flashes after `steps` steps, 100 by default"),
+            Part::Solved("the first step when all the octopuses flash"),
+        ],
+        tests: 4,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&STEPS, &MAX_STEPS];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Grid::parse_digits(input)
+    }
+
+    fn part1(octopuses: &Self::Input) -> usize {
+        total_flashes(octopuses, STEPS.get())
+    }
+
+    fn part2(octopuses: &Self::Input) -> Answer {
+        match first_simultaneous_step(octopuses, MAX_STEPS.get()) {
+            Some(step) => step.into(),
+            None => Answer::Text("never synchronizes".to_string()),
+        }
+    }
 }
 
 #[test]
 fn test_square() {
-    assert_eq!(square(0, 0), vec![(1, 0), (0, 1), (1, 1)]);
+    let grid = Grid::new(10, 10, 0);
+    assert_eq!(
+        grid.neighbors8(0, 0).collect::<Vec<_>>(),
+        vec![(1, 0), (0, 1), (1, 1)]
+    );
+}
+
+#[test]
+fn test_total_flashes() {
+    const INPUT: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+    let octopuses = Day11::parse(INPUT).unwrap();
+    assert_eq!(total_flashes(&octopuses, 100), 1656);
+    assert_eq!(first_simultaneous_step(&octopuses, 1000), Some(195));
+    assert_eq!(first_simultaneous_step(&octopuses, 194), None);
+}
+
+#[test]
+fn test_never_synchronizes() {
+    // These octopuses fall into a cycle where they never flash all at once.
+    let octopuses = Day11::parse("00\n23\n").unwrap();
+    assert_eq!(first_simultaneous_step(&octopuses, 1000), None);
+    assert_eq!(
+        Day11::part2(&octopuses),
+        Answer::Text("never synchronizes".to_string())
+    );
+}
+
+#[test]
+fn test_empty_grid() {
+    let octopuses = Day11::parse("").unwrap();
+    assert_eq!(Day11::part1(&octopuses), 0);
+    assert_eq!(first_simultaneous_step(&octopuses, 10), Some(1));
 }
@@ -1,9 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
+use crate::differential::{self, Outcome};
+use crate::meta::{Meta, Part};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
 #[derive(Clone)]
-struct CavePath {
-    caves: (String, String),
+pub struct CavePath {
+    pub caves: (String, String),
 }
 
 impl CavePath {
@@ -19,10 +25,13 @@ impl CavePath {
 }
 
 impl FromStr for CavePath {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<CavePath, Self::Err> {
-        let caves = s.split_once('-').unwrap();
+        let caves = s
+            .split_once('-')
+            .filter(|(a, b)| !a.is_empty() && !b.is_empty())
+            .ok_or_else(|| ParseError::at(s, s, "two caves joined by '-'"))?;
         Ok(CavePath {
             caves: (caves.0.to_string(), caves.1.to_string()),
         })
@@ -37,12 +46,10 @@ fn is_small_cave(cave: &str) -> bool {
     }
 }
 
-fn count_paths(input: &str, allow_twice: bool) -> usize {
-    let paths = input
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<CavePath>>();
-    count_paths_recursive("start", &paths, &HashMap::from([("start", 2)]), allow_twice)
+/// Counts the paths from start to end that visit small caves at most once,
+/// or one of them twice with `allow_twice`.
+pub fn count_paths(paths: &[CavePath], allow_twice: bool) -> usize {
+    count_paths_recursive("start", paths, &HashMap::from([("start", 2)]), allow_twice)
 }
 
 fn count_paths_recursive(
@@ -74,10 +81,127 @@ fn count_paths_recursive(
     sum
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day12.txt");
-    println!("solution {}", count_paths(INPUT, false));
-    println!("solution {}", count_paths(INPUT, true));
+/// Counts the paths by listing every one of them, as a reference for
+/// [`count_paths`].
+pub fn count_paths_reference(paths: &[CavePath], allow_twice: bool) -> usize {
+    let allowed = |route: &[&str]| {
+        let mut visits = HashMap::new();
+        for cave in route.iter().filter(|cave| is_small_cave(cave)) {
+            *visits.entry(*cave).or_insert(0) += 1;
+        }
+        let twice = visits.values().filter(|visits| **visits == 2).count();
+        visits.values().all(|visits| *visits <= 2) && twice <= allow_twice as usize
+    };
+    let mut found = HashSet::new();
+    let mut routes = vec![vec!["start"]];
+    while let Some(route) = routes.pop() {
+        let cave = route[route.len() - 1];
+        if cave == "end" {
+            found.insert(route);
+            continue;
+        }
+        for next in paths.iter().filter_map(|path| path.from(cave)) {
+            let mut longer = route.clone();
+            longer.push(next);
+            if next != "start" && allowed(&longer) {
+                routes.push(longer);
+            }
+        }
+    }
+    found.len()
+}
+
+pub fn differential(input: &str) -> Outcome {
+    match Day12::parse(input) {
+        Ok(paths) => differential::compare(
+            (count_paths(&paths, false), count_paths(&paths, true)),
+            (
+                count_paths_reference(&paths, false),
+                count_paths_reference(&paths, true),
+            ),
+        ),
+        Err(_) => Outcome::Invalid,
+    }
+}
+
+fn cave_name(i: usize, big: bool) -> String {
+    let base = if big { b'A' } else { b'a' };
+    let name = [base + (i / 26 % 26) as u8, base + (i % 26) as u8];
+    String::from_utf8_lossy(&name).into_owned()
+}
+
+/// Adds a passage unless the caves are already joined, either way round.
+fn join(paths: &mut Vec<(String, String)>, a: &str, b: &str) {
+    if !paths
+        .iter()
+        .any(|(c, d)| (c == a && d == b) || (c == b && d == a))
+    {
+        paths.push((a.to_string(), b.to_string()));
+    }
+}
+
+/// A connected graph of `size` caves where no two big caves are adjacent, so
+/// that the number of paths is finite, and no passage is listed twice.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let small = (size - size / 4).max(1);
+    let small_caves = (0..small).map(|i| cave_name(i, false)).collect::<Vec<_>>();
+    let mut paths = vec![("start".to_string(), small_caves[0].clone())];
+    for i in 1..small {
+        let from = if rng.chance(1, 4) {
+            "start".to_string()
+        } else {
+            small_caves[rng.index(i)].clone()
+        };
+        paths.push((from, small_caves[i].clone()));
+    }
+    for _ in 0..small / 3 {
+        let (a, b) = (rng.choose(&small_caves), rng.choose(&small_caves));
+        if a != b {
+            join(&mut paths, a, b);
+        }
+    }
+    for i in 0..size / 4 {
+        let big = cave_name(i, true);
+        for _ in 0..rng.range(1..4) {
+            let small_cave = rng.choose(&small_caves);
+            join(&mut paths, &big, small_cave);
+        }
+    }
+    paths.push((rng.choose(&small_caves).clone(), "end".to_string()));
+    rng.shuffle(&mut paths);
+    paths
+        .iter()
+        .map(|(a, b)| format!("{}-{}\n", a, b))
+        .collect()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<CavePath>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Passage Pathing",
+        parts: [
+            Part::Solved("the number of paths visiting small caves at most once"),
+            Part::Solved("the same when one small cave can be visited twice"),
+        ],
+        tests: 4,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+    }
+
+    fn part1(paths: &Self::Input) -> usize {
+        count_paths(paths, false)
+    }
+
+    fn part2(paths: &Self::Input) -> usize {
+        count_paths(paths, true)
+    }
 }
 
 #[test]
@@ -89,7 +213,7 @@ A-b
 b-d
 A-end
 b-end";
-    assert_eq!(count_paths(INPUT, false), 10);
+    assert_eq!(count_paths(&Day12::parse(INPUT).unwrap(), false), 10);
 }
 
 #[test]
@@ -101,7 +225,7 @@ A-b
 b-d
 A-end
 b-end";
-    assert_eq!(count_paths(INPUT, true), 36);
+    assert_eq!(count_paths(&Day12::parse(INPUT).unwrap(), true), 36);
 }
 
 #[test]
@@ -124,7 +248,7 @@ he-WI
 zg-he
 pj-fs
 start-RW";
-    assert_eq!(count_paths(INPUT, false), 226);
+    assert_eq!(count_paths(&Day12::parse(INPUT).unwrap(), false), 226);
 }
 
 #[test]
@@ -147,5 +271,5 @@ he-WI
 zg-he
 pj-fs
 start-RW";
-    assert_eq!(count_paths(INPUT, true), 3509);
+    assert_eq!(count_paths(&Day12::parse(INPUT).unwrap(), true), 3509);
 }
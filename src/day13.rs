@@ -1,18 +1,28 @@
 use std::collections::HashSet;
 use std::str::FromStr;
 
-enum Axis {
+use crate::frames::Color;
+use crate::grid::Grid;
+use crate::input;
+use crate::meta::{Meta, Part};
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Clone, Copy)]
+pub enum Axis {
     X,
     Y,
 }
 
-struct FoldInstruction {
-    axis: Axis,
-    position: usize,
+pub struct FoldInstruction {
+    pub axis: Axis,
+    pub position: usize,
 }
 
 impl FoldInstruction {
-    fn wrap(&self, dot: (usize, usize)) -> (usize, usize) {
+    /// Where a dot lands once the paper is folded.
+    pub fn wrap(&self, dot: (usize, usize)) -> (usize, usize) {
         match self.axis {
             Axis::X if dot.0 >= self.position => (2 * self.position - dot.0, dot.1),
             Axis::Y if dot.1 >= self.position => (dot.0, 2 * self.position - dot.1),
@@ -22,61 +32,169 @@ impl FoldInstruction {
 }
 
 impl FromStr for FoldInstruction {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<FoldInstruction, Self::Err> {
-        let (a, p) = s[11..].split_once('=').unwrap();
-        let axis = match a {
-            "x" => Axis::X,
-            "y" => Axis::Y,
-            _ => return Err(()),
-        };
-        let position = p.parse().unwrap();
+        let mut cursor = Cursor::new(s, s);
+        cursor.literal("fold along ")?;
+        let axis = cursor.one_of(&[("x", Axis::X), ("y", Axis::Y)], "x or y")?;
+        cursor.literal("=")?;
+        let position = cursor.number()?;
+        cursor.end()?;
         Ok(FoldInstruction { axis, position })
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day13.txt");
-    let sections = INPUT.split_once("\n\n").unwrap();
-    let mut dots = sections
-        .0
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once(',').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect::<Vec<(usize, usize)>>();
-    let instructions = sections
-        .1
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<FoldInstruction>>();
-
-    let mut all_dots: HashSet<(usize, usize)> = HashSet::new();
-    for dot in &dots {
-        all_dots.insert(instructions[0].wrap(*dot));
+pub struct Manual {
+    pub dots: Vec<(usize, usize)>,
+    pub instructions: Vec<FoldInstruction>,
+}
+
+impl Manual {
+    /// The dots left after the first `folds` instructions.
+    pub fn fold(&self, folds: usize) -> HashSet<(usize, usize)> {
+        let mut dots = self.dots.iter().copied().collect::<HashSet<_>>();
+        for instruction in self.instructions.iter().take(folds) {
+            dots = dots.iter().map(|dot| instruction.wrap(*dot)).collect();
+        }
+        dots
     }
-    println!("solution {}", all_dots.len());
+}
+
+const FOLDS: [(char, usize); 12] = [
+    ('x', 655),
+    ('y', 447),
+    ('x', 327),
+    ('y', 223),
+    ('x', 163),
+    ('y', 111),
+    ('x', 81),
+    ('y', 55),
+    ('x', 40),
+    ('y', 27),
+    ('y', 13),
+    ('y', 6),
+];
+
+/// The paper, then dots.
+pub const PALETTE: [Color; 2] = [[16, 16, 16], [160, 70, 220]];
 
-    all_dots.drain();
-    for instr in instructions {
-        for dot in &dots {
-            all_dots.insert(instr.wrap(*dot));
+/// The dots on the unfolded sheet, then after each fold, in the top left corner.
+pub fn frames(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError> {
+    let manual = Day13::parse(input)?;
+    let (mut width, mut height) = (1, 1);
+    for &(x, y) in &manual.dots {
+        width = width.max(x + 1);
+        height = height.max(y + 1);
+    }
+    for instruction in &manual.instructions {
+        match instruction.axis {
+            Axis::X => width = width.max(2 * instruction.position + 1),
+            Axis::Y => height = height.max(2 * instruction.position + 1),
+        }
+    }
+    let mut dots = manual.dots.clone();
+    let draw = |dots: &[(usize, usize)]| {
+        let mut sheet = Grid::new(width, height, 0);
+        for &dot in dots {
+            sheet[dot] = 1;
         }
-        dots = all_dots.drain().collect();
+        sheet
+    };
+    frame(&draw(&dots));
+    for instruction in &manual.instructions {
+        dots = dots.iter().map(|dot| instruction.wrap(*dot)).collect();
+        frame(&draw(&dots));
     }
-    for y in 0..6 {
-        println!(
-            "{}",
-            (0..40)
-                .map(|x| if dots.contains(&(x, y)) {
-                    '🟪'
-                } else {
-                    '⬛'
-                })
-                .collect::<String>()
-        );
+    Ok(())
+}
+
+/// Unfolds random dots of the final 40 by 6 code, so that no dot is on a fold.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let (mut x, mut y) = (rng.below(40), rng.below(6));
+        for (axis, position) in FOLDS.iter().rev() {
+            let position = *position as u64;
+            if rng.chance(1, 2) {
+                match axis {
+                    'x' => x = 2 * position - x,
+                    _ => y = 2 * position - y,
+                }
+            }
+        }
+        input += &format!("{},{}\n", x, y);
+    }
+    input.push('\n');
+    for (axis, position) in FOLDS {
+        input += &format!("fold along {}={}\n", axis, position);
+    }
+    input
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Manual;
+    type Part1 = usize;
+    type Part2 = Vec<String>;
+
+    const META: Meta = Meta {
+        title: "Transparent Origami",
+        parts: [
+            Part::Solved("the number of dots after the first fold"),
+            Part::Solved("the code drawn by the dots after all the folds"),
+        ],
+        tests: 1,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (dots, instructions) = match input::sections(input)[..] {
+            [dots, instructions] => (dots, instructions),
+            [_, _, extra, ..] => return Err(ParseError::at(input, extra, "the end of the input")),
+            _ => {
+                return Err(ParseError::end(
+                    input,
+                    "a blank line before the instructions",
+                ))
+            }
+        };
+        let dots = dots
+            .lines()
+            .map(|line| {
+                let mut cursor = Cursor::new(input, line);
+                let dot = cursor.pair(",")?;
+                cursor.end()?;
+                Ok(dot)
+            })
+            .collect::<Result<Vec<(usize, usize)>, ParseError>>()?;
+        let instructions = parse::lines::<FoldInstruction>(instructions)
+            .map_err(|e| e.within(input, instructions))?;
+        if instructions.is_empty() {
+            return Err(ParseError::end(input, "a fold instruction"));
+        }
+        Ok(Manual { dots, instructions })
+    }
+
+    fn part1(manual: &Self::Input) -> usize {
+        manual.fold(1).len()
+    }
+
+    fn part2(manual: &Self::Input) -> Vec<String> {
+        let dots = manual.fold(manual.instructions.len());
+        (0..6)
+            .map(|y| {
+                (0..40)
+                    .map(|x| {
+                        if dots.contains(&(x, y)) {
+                            '🟪'
+                        } else {
+                            '⬛'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect()
     }
 }
 
@@ -1,6 +1,17 @@
 use std::collections::HashMap;
 
-fn polymerize(depth: usize, template: &str, rules: &[(char, char, char)]) -> (usize, usize) {
+use crate::checked;
+use crate::input;
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::params::{Param, Tunable};
+use crate::parse::{Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+/// Applies the pair insertion rules `depth` times, returning the counts of the
+/// least and most common elements.
+pub fn polymerize(depth: usize, template: &str, rules: &[(char, char, char)]) -> (usize, usize) {
     let chars = &template.chars().collect::<Vec<char>>();
 
     let mut elements: HashMap<char, usize> = HashMap::new();
@@ -22,7 +33,8 @@ fn polymerize(depth: usize, template: &str, rules: &[(char, char, char)]) -> (us
             {
                 *(p.entry((pair.0, rule.2)).or_insert(0)) += n;
                 *(p.entry((rule.2, pair.1)).or_insert(0)) += n;
-                *(p.entry((pair.0, pair.1)).or_insert(0)) -= n;
+                let count = p.entry((pair.0, pair.1)).or_insert(0);
+                *count = checked::sub(*count, *n);
                 *(elements.entry(rule.2).or_insert(0)) += n;
             }
         }
@@ -34,27 +46,96 @@ fn polymerize(depth: usize, template: &str, rules: &[(char, char, char)]) -> (us
     (*least_common, *most_common)
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day14.txt");
-    let (template, rules) = INPUT.split_once("\n\n").unwrap();
-    let rules = rules
-        .lines()
-        .map(|rule| rule.split_once(" -> ").unwrap())
-        .map(|(a, b)| {
-            let mut pair = a.chars();
-            (
-                pair.next().unwrap(),
-                pair.next().unwrap(),
-                b.chars().next().unwrap(),
-            )
+pub struct Manual {
+    pub template: String,
+    pub rules: Vec<(char, char, char)>,
+}
+
+/// A template of `size` elements with a rule for every pair of elements.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let elements = "BCFHKNOPSV".chars().collect::<Vec<char>>();
+    let mut input = (0..size.max(2))
+        .map(|_| *rng.choose(&elements))
+        .collect::<String>();
+    input.push_str("\n\n");
+    for a in &elements {
+        for b in &elements {
+            input += &format!("{}{} -> {}\n", a, b, rng.choose(&elements));
+        }
+    }
+    input
+}
+
+const PART1_STEPS: Param<usize> = Param::new("part1_steps", "10", "insertion steps in part 1");
+const PART2_STEPS: Param<usize> = Param::new("part2_steps", "40", "insertion steps in part 2");
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Manual;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Extended Polymerization",
+        parts: [
+            Part::Solved(
+                "the most common element count minus the least common after `part1_steps` steps, 10 by default",
+            ),
+            Part::Solved("the same after `part2_steps` steps, 40 by default"),
+        ],
+        tests: 1,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&PART1_STEPS, &PART2_STEPS];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (template, rules) = match input::sections(input)[..] {
+            [template, rules] => (template, rules),
+            [_, _, extra, ..] => return Err(ParseError::at(input, extra, "the end of the input")),
+            _ => return Err(ParseError::end(input, "a blank line before the rules")),
+        };
+        if template.is_empty() || !template.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(ParseError::at(input, template, "a polymer template"));
+        }
+        let rules = rules
+            .lines()
+            .map(|rule| {
+                let mut cursor = Cursor::new(input, rule);
+                let pair = cursor.token("a pair of elements")?;
+                let [a, b] = pair.chars().collect::<Vec<char>>()[..] else {
+                    return Err(cursor.error(pair, "a pair of elements"));
+                };
+                cursor.literal(" -> ")?;
+                let insert = cursor.token("an element")?;
+                let [c] = insert.chars().collect::<Vec<char>>()[..] else {
+                    return Err(cursor.error(insert, "an element"));
+                };
+                cursor.end()?;
+                Ok((a, b, c))
+            })
+            .collect::<Result<Vec<(char, char, char)>, ParseError>>()?;
+        Ok(Manual {
+            template: template.to_string(),
+            rules,
         })
-        .collect::<Vec<(char, char, char)>>();
+    }
 
-    let (least_common, most_common) = polymerize(10, template, &rules);
-    println!("solution {}", most_common - least_common);
+    fn part1(manual: &Self::Input) -> usize {
+        let (least_common, most_common) =
+            polymerize(PART1_STEPS.get(), &manual.template, &manual.rules);
+        notes::note("least_common", least_common);
+        notes::note("most_common", most_common);
+        most_common - least_common
+    }
 
-    let (least_common, most_common) = polymerize(40, template, &rules);
-    println!("solution {}", most_common - least_common);
+    fn part2(manual: &Self::Input) -> usize {
+        let (least_common, most_common) =
+            polymerize(PART2_STEPS.get(), &manual.template, &manual.rules);
+        notes::note("least_common", least_common);
+        notes::note("most_common", most_common);
+        most_common - least_common
+    }
 }
 
 #[test]
@@ -1,135 +1,108 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Vertex {
-    position: (usize, usize),
-    risk: u32,
+use crate::event;
+use crate::grid::Grid;
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::search::{self, Search, Stats};
+use crate::{Answer, Solution};
+
+/// Finds the least risky path from the top left to the bottom right, guided by
+/// the Manhattan distance since every level is at least 1. An empty map has
+/// no path.
+pub fn shortest_path(levels: &Grid<u32>) -> Search<(usize, usize), u32> {
+    if levels.width() == 0 || levels.height() == 0 {
+        return Search {
+            route: None,
+            stats: Stats::default(),
+        };
+    }
+    let target = (levels.width() - 1, levels.height() - 1);
+    search::astar(
+        (0, 0),
+        |&(x, y)| {
+            event!(Trace, "expanded", x = x, y = y);
+            levels
+                .neighbors4(x, y)
+                .map(|position| (position, levels[position]))
+                .collect::<Vec<_>>()
+        },
+        |&(x, y)| (target.0 - x + target.1 - y) as u32,
+        |&position| position == target,
+    )
 }
 
-impl Ord for Vertex {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .risk
-            .cmp(&self.risk)
-            .then_with(|| self.position.cmp(&other.position))
-    }
+/// The total risk of the least risky path, if there is one.
+pub fn lowest_risk(levels: &Grid<u32>) -> Option<u32> {
+    let search = shortest_path(levels);
+    notes::note("expanded", search.stats.expanded);
+    notes::note("max_frontier", search.stats.max_frontier);
+    let route = search.route?;
+    event!(
+        Info,
+        "searched",
+        risk = route.cost,
+        path = route.path.len(),
+        expanded = search.stats.expanded,
+        max_frontier = search.stats.max_frontier
+    );
+    Some(route.cost)
 }
 
-impl PartialOrd for Vertex {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// The full map, five times larger in each direction.
+pub fn expand_map(risk_map: &Grid<u32>) -> Grid<u32> {
+    let (width, height) = (risk_map.width(), risk_map.height());
+    if width == 0 {
+        return risk_map.clone();
     }
+    let levels = (0..height * 5)
+        .flat_map(|y| {
+            (0..width * 5).map(move |x| {
+                let tile = (x / width + y / height) as u32;
+                (risk_map[(x % width, y % height)] + tile - 1) % 9 + 1
+            })
+        })
+        .collect();
+    Grid::from_vec(width * 5, levels)
 }
 
-struct RiskMap {
-    levels: Vec<Vec<u32>>,
-    size: usize,
-    target: (usize, usize),
-    risks: Vec<u32>,
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(1);
+    let levels = (0..size * size).map(|_| rng.range(1..10) as u32).collect();
+    Grid::from_vec(size, levels).to_string()
 }
 
-impl RiskMap {
-    fn new(levels: Vec<Vec<u32>>) -> RiskMap {
-        let size = levels.len();
-        RiskMap {
-            levels,
-            size,
-            target: (size - 1, size - 1),
-            risks: (0..size * size).map(|_| u32::MAX).collect(),
-        }
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Grid<u32>;
+    type Part1 = Answer;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Chiton",
+        parts: [
+            Part::Solved("the lowest total risk of a path across the map"),
+            Part::Solved("the same across the map repeated five times each way"),
+        ],
+        tests: 4,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        // The search's heuristic relies on every level being at least 1.
+        Grid::parse(input, "a risk level from 1 to 9", |c| {
+            c.to_digit(10).filter(|level| *level > 0)
+        })
     }
 
-    fn explore(&mut self, position: (usize, usize), risk: u32) -> Option<Vertex> {
-        let next_risk = risk + self.levels[position.1][position.0];
-        if next_risk < self.risks[position.1 * self.size + position.0] {
-            self.risks[position.1 * self.size + position.0] = next_risk;
-            Some(Vertex {
-                position,
-                risk: next_risk,
-            })
-        } else {
-            None
-        }
-    }
-
-    fn shortest_path(&mut self) -> Option<u32> {
-        let mut paths: BinaryHeap<Vertex> = BinaryHeap::new();
-
-        paths.push(Vertex {
-            position: (0, 0),
-            risk: 0,
-        });
-
-        while let Some(Vertex { position, risk }) = paths.pop() {
-            if position == self.target {
-                return Some(risk);
-            }
-
-            if risk > self.risks[position.1 * self.size + position.0] {
-                continue;
-            }
-
-            if position.0 > 0 {
-                if let Some(next) = self.explore((position.0 - 1, position.1), risk) {
-                    paths.push(next);
-                }
-            }
-            if position.0 < self.size - 1 {
-                if let Some(next) = self.explore((position.0 + 1, position.1), risk) {
-                    paths.push(next);
-                }
-            }
-            if position.1 > 0 {
-                if let Some(next) = self.explore((position.0, position.1 - 1), risk) {
-                    paths.push(next);
-                }
-            }
-            if position.1 < self.size - 1 {
-                if let Some(next) = self.explore((position.0, position.1 + 1), risk) {
-                    paths.push(next);
-                }
-            }
-        }
-
-        None
+    fn part1(risk_level: &Self::Input) -> Answer {
+        lowest_risk(risk_level).map_or(Answer::Text("no path".to_string()), Answer::from)
     }
-}
 
-fn expand_map(risk_map: &[Vec<u32>]) -> Vec<Vec<u32>> {
-    let mut result = Vec::new();
-    for i in 0..5 {
-        for row in risk_map {
-            let rows = [
-                row.iter()
-                    .map(|x| (x + i - 1) % 9 + 1)
-                    .collect::<Vec<u32>>(),
-                row.iter().map(|x| (x + i) % 9 + 1).collect(),
-                row.iter().map(|x| (x + i + 1) % 9 + 1).collect(),
-                row.iter().map(|x| (x + i + 2) % 9 + 1).collect(),
-                row.iter().map(|x| (x + i + 3) % 9 + 1).collect(),
-            ];
-            result.push(rows.concat());
-        }
+    fn part2(risk_level: &Self::Input) -> Answer {
+        lowest_risk(&expand_map(risk_level))
+            .map_or(Answer::Text("no path".to_string()), Answer::from)
     }
-    result
-}
-
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day15.txt");
-    let risk_level = INPUT
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect::<Vec<Vec<u32>>>();
-    let mut risk_map = RiskMap::new(risk_level);
-    println!("solution {}", risk_map.shortest_path().unwrap());
-
-    let risk_level = INPUT
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect::<Vec<Vec<u32>>>();
-    let mut risk_map = RiskMap::new(expand_map(&risk_level));
-    println!("solution {}", risk_map.shortest_path().unwrap());
 }
 
 #[test]
@@ -144,12 +117,11 @@ fn test_shortest_path() {
 3125421639
 1293138521
 2311944581";
-    let risk_level = INPUT
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect::<Vec<Vec<u32>>>();
-    let mut risk_map = RiskMap::new(risk_level);
-    assert_eq!(risk_map.shortest_path().unwrap(), 40);
+    let risk_level = Grid::parse_digits(INPUT).unwrap();
+    let route = shortest_path(&risk_level).route.unwrap();
+    assert_eq!(route.cost, 40);
+    assert_eq!(route.path.first(), Some(&(0, 0)));
+    assert_eq!(route.path.last(), Some(&(9, 9)));
 }
 
 #[test]
@@ -164,10 +136,22 @@ fn test_expand_map() {
 3125421639
 1293138521
 2311944581";
-    let risk_level = INPUT
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect::<Vec<Vec<u32>>>();
-    let mut risk_map = RiskMap::new(expand_map(&risk_level));
-    assert_eq!(risk_map.shortest_path().unwrap(), 315);
+    let risk_level = Grid::parse_digits(INPUT).unwrap();
+    assert_eq!(
+        shortest_path(&expand_map(&risk_level)).route.unwrap().cost,
+        315
+    );
+}
+
+#[test]
+fn test_empty_map() {
+    let levels = Grid::parse_digits("").unwrap();
+    assert_eq!(shortest_path(&levels).route, None);
+    assert_eq!(Day15::part2(&levels), Answer::Text("no path".to_string()));
+}
+
+#[test]
+fn test_zero_level() {
+    let e = Day15::parse("500\n011\n191\n").unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (1, 2, "0"));
 }
@@ -1,16 +1,21 @@
-use std::num::ParseIntError;
 use std::str::FromStr;
 
+use crate::checked;
+use crate::meta::{Meta, Part};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::Solution;
+
 #[derive(PartialEq, Clone, Debug)]
-enum PacketPayload {
+pub enum PacketPayload {
     Literal(u64),
     Operator(u32, Vec<Packet>),
 }
 
 #[derive(Clone, Debug)]
-struct Packet {
-    version: u32,
-    payload: PacketPayload,
+pub struct Packet {
+    pub version: u32,
+    pub payload: PacketPayload,
 }
 
 impl PartialEq for Packet {
@@ -19,103 +24,175 @@ impl PartialEq for Packet {
     }
 }
 
-type ParsePacketResult = Result<Packet, ParseIntError>;
+/// Packets nested deeper than this are rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 100;
+
+/// What the parser expected when it failed.
+type ParsePacketResult<T> = Result<T, &'static str>;
 
 struct PacketParser<'a> {
     s: std::str::Chars<'a>,
+    depth: usize,
 }
 
 impl PacketParser<'_> {
-    fn new(s: &str) -> PacketParser {
-        PacketParser { s: s.chars() }
+    fn new(s: &str, depth: usize) -> PacketParser<'_> {
+        PacketParser {
+            s: s.chars(),
+            depth,
+        }
     }
 
-    fn take_string(&mut self, n: usize) -> String {
-        (&mut self.s).take(n).collect()
+    fn take_string(&mut self, n: usize) -> ParsePacketResult<String> {
+        let s = (&mut self.s).take(n).collect::<String>();
+        if s.len() == n {
+            Ok(s)
+        } else {
+            Err("more packet bits")
+        }
     }
 
-    fn parse_number(&mut self, bits: usize) -> Result<u32, ParseIntError> {
-        u32::from_str_radix(&self.take_string(bits), 2)
+    fn parse_number(&mut self, bits: usize) -> ParsePacketResult<u32> {
+        Ok(u32::from_str_radix(&self.take_string(bits)?, 2).expect("bits are binary digits"))
     }
 
-    fn parse_operator(&mut self, id: u32) -> PacketPayload {
-        let mut packets;
-        match self.parse_number(1).unwrap() {
+    fn parse_operator(&mut self, id: u32) -> ParsePacketResult<PacketPayload> {
+        if self.depth == MAX_DEPTH {
+            return Err("packets nested less deeply");
+        }
+        let mut packets = Vec::new();
+        match self.parse_number(1)? {
             0 => {
-                let len = self.parse_number(15).unwrap() as usize;
-                let substring = self.take_string(len);
-                let sub_parser = &mut PacketParser::new(&substring);
-                packets = Vec::new();
-                while let Ok(packet) = sub_parser.parse_packet() {
-                    packets.push(packet);
+                let len = self.parse_number(15)? as usize;
+                let substring = self.take_string(len)?;
+                let sub_parser = &mut PacketParser::new(&substring, self.depth + 1);
+                while !sub_parser.s.as_str().is_empty() {
+                    packets.push(sub_parser.parse_packet()?);
                 }
             }
-            1 => {
-                let n = self.parse_number(11).unwrap();
-                packets = Vec::with_capacity(n as usize);
+            _ => {
+                let n = self.parse_number(11)?;
+                self.depth += 1;
                 for _ in 0..n {
-                    packets.push(self.parse_packet().unwrap());
+                    packets.push(self.parse_packet()?);
                 }
+                self.depth -= 1;
             }
-            _ => unreachable!(),
         }
-        PacketPayload::Operator(id, packets)
+        match (id, packets.len()) {
+            (_, 0) => Err("at least one sub-packet"),
+            (5..=7, n) if n != 2 => Err("exactly two sub-packets to compare"),
+            _ => Ok(PacketPayload::Operator(id, packets)),
+        }
     }
 
-    fn parse_literal(&mut self) -> PacketPayload {
+    fn parse_literal(&mut self) -> ParsePacketResult<PacketPayload> {
         let mut n = 0_u64;
         loop {
-            let first_bit = self.s.next().unwrap();
-            n = (n << 1) + self.s.next().unwrap().to_digit(2).unwrap() as u64;
-            n = (n << 1) + self.s.next().unwrap().to_digit(2).unwrap() as u64;
-            n = (n << 1) + self.s.next().unwrap().to_digit(2).unwrap() as u64;
-            n = (n << 1) + self.s.next().unwrap().to_digit(2).unwrap() as u64;
-            if first_bit == '0' {
+            let group = self.parse_number(5)?;
+            n = checked::shl(n, 4) + (group & 0xf) as u64;
+            if group & 0x10 == 0 {
                 break;
             }
         }
-        PacketPayload::Literal(n)
+        Ok(PacketPayload::Literal(n))
     }
 
-    fn parse_packet(&mut self) -> ParsePacketResult {
+    fn parse_packet(&mut self) -> ParsePacketResult<Packet> {
         let version = self.parse_number(3)?;
         let payload = match self.parse_number(3)? {
-            4 => self.parse_literal(),
-            id => self.parse_operator(id),
+            4 => self.parse_literal()?,
+            id => self.parse_operator(id)?,
         };
         Ok(Packet { version, payload })
     }
 }
 
 impl FromStr for Packet {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> ParsePacketResult {
-        let s = s
-            .chars()
-            .map(|c| c.to_digit(16).unwrap())
-            .map(|n| format!("{:04b}", n))
-            .collect::<String>();
-        let mut parser = PacketParser::new(&s);
-        parser.parse_packet()
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Packet, Self::Err> {
+        let bits = s
+            .char_indices()
+            .map(|(i, c)| {
+                c.to_digit(16)
+                    .map(|n| format!("{:04b}", n))
+                    .ok_or_else(|| ParseError::at(s, &s[i..i + c.len_utf8()], "a hex digit"))
+            })
+            .collect::<Result<String, ParseError>>()?;
+        let mut parser = PacketParser::new(&bits, 0);
+        parser
+            .parse_packet()
+            .map_err(|expected| ParseError::end(s, expected))
     }
 }
 
-fn sum_version_numbers(p: &Packet) -> u32 {
+/// An operator with too many sub-packets for either length type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TooManyPackets;
+
+impl Packet {
+    /// Encodes the packet in hex, the way it's transmitted.
+    pub fn encode(&self) -> Result<String, TooManyPackets> {
+        self.bits().map(hex)
+    }
+
+    /// Encodes the packet as bits, with the length of sub-packets in bits
+    /// whenever it fits, or else their number. Fails when neither fits.
+    fn bits(&self) -> Result<String, TooManyPackets> {
+        match &self.payload {
+            PacketPayload::Literal(value) => Ok(literal_bits(self.version as u64, *value)),
+            PacketPayload::Operator(id, packets) => {
+                let content = packets
+                    .iter()
+                    .map(Packet::bits)
+                    .collect::<Result<String, TooManyPackets>>()?;
+                if content.len() < 1 << 15 {
+                    Ok(format!(
+                        "{:03b}{:03b}0{:015b}{}",
+                        self.version,
+                        id,
+                        content.len(),
+                        content
+                    ))
+                } else if packets.len() < 1 << 11 {
+                    Ok(format!(
+                        "{:03b}{:03b}1{:011b}{}",
+                        self.version,
+                        id,
+                        packets.len(),
+                        content
+                    ))
+                } else {
+                    Err(TooManyPackets)
+                }
+            }
+        }
+    }
+}
+
+/// The sum of the versions of the packet and all its sub-packets.
+pub fn sum_version_numbers(p: &Packet) -> u32 {
     match &p.payload {
-        PacketPayload::Literal(_) => p.version as u32,
+        PacketPayload::Literal(_) => p.version,
         PacketPayload::Operator(_, subpackets) => {
             p.version + subpackets.iter().map(sum_version_numbers).sum::<u32>()
         }
     }
 }
 
-fn eval_packet(p: &Packet) -> u64 {
+pub fn eval_packet(p: &Packet) -> u64 {
     match &p.payload {
-        PacketPayload::Literal(n) => *n as u64,
+        PacketPayload::Literal(n) => *n,
         PacketPayload::Operator(id, subpackets) => match id {
-            0 => subpackets.iter().map(eval_packet).sum::<u64>(),
-            1 => subpackets.iter().map(eval_packet).product::<u64>(),
+            0 => subpackets
+                .iter()
+                .map(eval_packet)
+                .fold(0, |sum, value| checked::add(sum, value)),
+            1 => subpackets
+                .iter()
+                .map(eval_packet)
+                .fold(1, |product, value| checked::mul(product, value)),
             2 => subpackets.iter().map(eval_packet).min().unwrap(),
             3 => subpackets.iter().map(eval_packet).max().unwrap(),
             5 => {
@@ -147,12 +224,105 @@ fn eval_packet(p: &Packet) -> u64 {
     }
 }
 
-fn main() -> Result<(), ParseIntError> {
-    let input = include_str!("../inputs/day16.txt").trim_end();
-    let packet: Packet = input.parse()?;
-    println!("solution {}", sum_version_numbers(&packet));
-    println!("solution {}", eval_packet(&packet));
-    Ok(())
+fn literal_bits(version: u64, value: u64) -> String {
+    let digits = format!("{:b}", value);
+    let digits = format!("{:0>width$}", digits, width = digits.len().div_ceil(4) * 4);
+    let groups = digits.as_bytes().chunks(4).collect::<Vec<_>>();
+    let mut bits = format!("{:03b}100", version);
+    for (i, group) in groups.iter().enumerate() {
+        bits.push(if i + 1 == groups.len() { '0' } else { '1' });
+        bits.push_str(std::str::from_utf8(group).unwrap());
+    }
+    bits
+}
+
+fn packet_bits(budget: &mut usize, rng: &mut Rng) -> String {
+    let version = rng.below(8);
+    *budget = budget.saturating_sub(1);
+    if *budget == 0 || rng.chance(1, 3) {
+        return literal_bits(version, rng.below(1 << 32));
+    }
+    let id = *rng.choose(&[0, 1, 2, 3, 5, 6, 7]);
+    let subpackets = match id {
+        // Products only of small literals, so that values fit in 64 bits.
+        1 => (0..rng.range(1..4))
+            .map(|_| {
+                *budget = budget.saturating_sub(1);
+                literal_bits(rng.below(8), rng.below(16))
+            })
+            .collect::<Vec<String>>(),
+        5..=7 => (0..2).map(|_| packet_bits(budget, rng)).collect(),
+        _ => (0..rng.range(1..5))
+            .map(|_| packet_bits(budget, rng))
+            .collect(),
+    };
+    let content = subpackets.concat();
+    if content.len() < 1 << 15 && rng.chance(1, 2) {
+        format!(
+            "{:03b}{:03b}0{:015b}{}",
+            version,
+            id,
+            content.len(),
+            content
+        )
+    } else {
+        format!(
+            "{:03b}{:03b}1{:011b}{}",
+            version,
+            id,
+            subpackets.len(),
+            content
+        )
+    }
+}
+
+/// Packs bits into uppercase hex digits, padding the end with zeros.
+fn hex(mut bits: String) -> String {
+    while !bits.len().is_multiple_of(4) {
+        bits.push('0');
+    }
+    bits.as_bytes()
+        .chunks(4)
+        .map(|nibble| {
+            let n = u32::from_str_radix(std::str::from_utf8(nibble).unwrap(), 2).unwrap();
+            char::from_digit(n, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect()
+}
+
+/// A transmission of about `size` packets.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut budget = size;
+    hex(packet_bits(&mut budget, rng)) + "\n"
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Packet;
+    type Part1 = u32;
+    type Part2 = u64;
+
+    const META: Meta = Meta {
+        title: "Packet Decoder",
+        parts: [
+            Part::Solved("the sum of the version numbers of all the packets"),
+            Part::Solved("the value of the outermost packet"),
+        ],
+        tests: 16,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.trim_end().parse()
+    }
+
+    fn part1(packet: &Self::Input) -> u32 {
+        sum_version_numbers(packet)
+    }
+
+    fn part2(packet: &Self::Input) -> u64 {
+        eval_packet(packet)
+    }
 }
 
 #[test]
@@ -167,6 +337,18 @@ fn test_literal() {
     );
 }
 
+#[test]
+fn test_long_literal() {
+    // 17 groups of four bits don't fit in a u64.
+    let packet = || "D3FFFFFFFFFFFFFFFFFFFDE".parse::<Packet>();
+    if checked::ENABLED {
+        let overflow = checked::catch(packet).unwrap_err();
+        assert_eq!((overflow.day, overflow.op), ("day16", "<<"));
+    } else {
+        assert!(packet().is_ok());
+    }
+}
+
 #[test]
 fn test_parse_type_0_operator() {
     let packet: Packet = "38006F45291200".parse().unwrap();
@@ -246,6 +428,22 @@ fn test_product_operator() {
     assert_eq!(eval_packet(&packet), 54);
 }
 
+#[test]
+fn test_product_overflow() {
+    let literal = Packet {
+        version: 0,
+        payload: PacketPayload::Literal(1 << 40),
+    };
+    let packet = Packet {
+        version: 0,
+        payload: PacketPayload::Operator(1, vec![literal; 2]),
+    };
+    if checked::ENABLED {
+        let overflow = checked::catch(|| eval_packet(&packet)).unwrap_err();
+        assert_eq!((overflow.day, overflow.op), ("day16", "*"));
+    }
+}
+
 #[test]
 fn test_minimum_operator() {
     let packet: Packet = "880086C3E88112".parse().unwrap();
@@ -281,3 +479,48 @@ fn test_nested_equal_operator() {
     let packet: Packet = "9C0141080250320F1802104A08".parse().unwrap();
     assert_eq!(eval_packet(&packet), 1);
 }
+
+#[cfg(test)]
+fn random_packet(depth: usize, rng: &mut Rng) -> Packet {
+    let version = rng.below(8) as u32;
+    if depth == 3 || rng.chance(1, 3) {
+        return Packet {
+            version,
+            payload: PacketPayload::Literal(rng.next_u64()),
+        };
+    }
+    let id = *rng.choose(&[0, 1, 2, 3, 5, 6, 7]);
+    let count = if id >= 5 { 2 } else { rng.range(1..5) };
+    let packets = (0..count).map(|_| random_packet(depth + 1, rng)).collect();
+    Packet {
+        version,
+        payload: PacketPayload::Operator(id, packets),
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    crate::rng::check(100, |rng| {
+        let packet = random_packet(0, rng);
+        assert_eq!(packet.encode().unwrap().parse::<Packet>().unwrap(), packet);
+    });
+}
+
+#[test]
+fn test_invalid_operators() {
+    // A minimum of no packets, and a comparison of one.
+    for transmission in ["080000", "16004408"] {
+        let e = transmission.parse::<Packet>().unwrap_err();
+        assert!(e.expected.contains("sub-packet"), "{}", e);
+    }
+    // 2048 literals of 16 bits don't fit either length type.
+    let literal = Packet {
+        version: 0,
+        payload: PacketPayload::Literal(16),
+    };
+    let packet = Packet {
+        version: 0,
+        payload: PacketPayload::Operator(0, vec![literal; 2048]),
+    };
+    assert_eq!(packet.encode(), Err(TooManyPackets));
+}
@@ -1,18 +1,51 @@
 use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::meta::{Meta, Part};
+use crate::params::{Param, Tunable};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
 
 #[derive(Clone, Debug)]
-struct Area {
-    x: RangeInclusive<i32>,
-    y: RangeInclusive<i32>,
+pub struct Area {
+    pub x: RangeInclusive<i32>,
+    pub y: RangeInclusive<i32>,
+}
+
+impl FromStr for Area {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The prefix is optional, so that an area can be given on its own.
+        let area = s.trim_end();
+        let (x, y) = area
+            .strip_prefix("target area: ")
+            .unwrap_or(area)
+            .strip_prefix("x=")
+            .and_then(|s| s.split_once(", y=").or_else(|| s.split_once(",y=")))
+            .ok_or_else(|| ParseError::at(s, s, "target area: x=A..B, y=C..D"))?;
+        let range = |r: &str| -> Result<RangeInclusive<i32>, ParseError> {
+            let (start, end) = r
+                .split_once("..")
+                .ok_or_else(|| ParseError::at(s, r, "a range like A..B"))?;
+            Ok(parse::number(s, start)?..=parse::number(s, end)?)
+        };
+        Ok(Area {
+            x: range(x)?,
+            y: range(y)?,
+        })
+    }
 }
 
 impl Area {
-    fn contains(&self, x: i32, y: i32) -> bool {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
         self.x.contains(&x) && self.y.contains(&y)
     }
 }
 
-fn simulate(area: &Area, dx: i32, dy: i32) -> Option<i32> {
+/// Launches the probe, returning its highest point if it hits the area.
+pub fn simulate(area: &Area, dx: i32, dy: i32) -> Option<i32> {
     let mut hit = false;
     let mut max_y = 0;
     for step in 0..1000 {
@@ -38,14 +71,15 @@ fn simulate(area: &Area, dx: i32, dy: i32) -> Option<i32> {
         }
     }
 
-    hit.then(|| max_y)
+    hit.then_some(max_y)
 }
 
-fn find_max_y(area: Area) -> i32 {
+/// The highest point of any launch that hits the area.
+pub fn find_max_y(area: &Area) -> i32 {
     let mut max_y = 0;
     for dx in 1..=*area.x.end() {
         for dy in 1..1000 {
-            if let Some(y) = simulate(&area, dx, dy) {
+            if let Some(y) = simulate(area, dx, dy) {
                 if y > max_y {
                     max_y = y;
                 }
@@ -55,11 +89,12 @@ fn find_max_y(area: Area) -> i32 {
     max_y
 }
 
-fn count_hits(area: Area) -> i32 {
+/// Counts the initial velocities that hit the area.
+pub fn count_hits(area: &Area) -> i32 {
     let mut counter = 0;
     for dx in 1..=*area.x.end() {
         for dy in *area.y.start()..1000 {
-            if simulate(&area, dx, dy).is_some() {
+            if simulate(area, dx, dy).is_some() {
                 counter += 1;
             }
         }
@@ -67,19 +102,63 @@ fn count_hits(area: Area) -> i32 {
     counter
 }
 
-fn main() {
-    let area = Area {
-        x: (179..=201),
-        y: (-109..=-63),
+/// An area around `size` to the right and below, which the probe can reach
+/// by stopping over it.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let dx = ((2 * size.max(1)) as f64).sqrt() as i64;
+    let stop = dx * (dx + 1) / 2;
+    let x = (stop - rng.range(0..10)).max(1)..=stop + rng.range(0..20);
+    let top = -(size.max(1) as i64) - rng.range(0..10);
+    let y = top - rng.range(5..30)..=top;
+    format!(
+        "target area: x={}..{}, y={}..{}\n",
+        x.start(),
+        x.end(),
+        y.start(),
+        y.end()
+    )
+}
+
+const AREA: Param<Area> = Param::from_input(
+    "area",
+    "target area such as x=20..30,y=-10..-5, in place of the input's",
+);
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Area;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    const META: Meta = Meta {
+        title: "Trick Shot",
+        parts: [
+            Part::Solved("the highest position reached by a shot hitting the target"),
+            Part::Solved("the number of initial velocities hitting the target"),
+        ],
+        tests: 3,
     };
-    println!("solution {}", find_max_y(area.clone()));
-    println!("solution {}", count_hits(area));
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&AREA];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(area: &Self::Input) -> i32 {
+        find_max_y(&AREA.get_or(area))
+    }
+
+    fn part2(area: &Self::Input) -> i32 {
+        count_hits(&AREA.get_or(area))
+    }
 }
 
 #[test]
 fn test_find_max_y() {
     assert_eq!(
-        find_max_y(Area {
+        find_max_y(&Area {
             x: (20..=30),
             y: (-10..=-5)
         }),
@@ -102,7 +181,7 @@ fn test_simulate() {
 #[test]
 fn test_count_hits() {
     assert_eq!(
-        count_hits(Area {
+        count_hits(&Area {
             x: (20..=30),
             y: (-10..=-5)
         }),
@@ -1,9 +1,14 @@
 use std::fmt;
 use std::ops::Add;
-use std::str::FromStr;
+use std::str::{CharIndices, FromStr};
 
-#[derive(Clone)]
-enum Value {
+use crate::meta::{Meta, Part};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
     Number(u32, usize),
     Pair(Box<Value>, Box<Value>),
 }
@@ -57,34 +62,78 @@ impl Add for Value {
     }
 }
 
-fn parse_pair(iter: &mut std::iter::Peekable<std::str::Chars>) -> Value {
-    if iter.next().unwrap() != '[' {
-        panic!();
+/// Pairs nested deeper than this are rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 100;
+
+struct ValueParser<'a> {
+    s: &'a str,
+    chars: CharIndices<'a>,
+    depth: usize,
+}
+
+impl<'a> ValueParser<'a> {
+    fn next(&mut self) -> &'a str {
+        match self.chars.next() {
+            Some((i, c)) => &self.s[i..i + c.len_utf8()],
+            None => &self.s[self.s.len()..],
+        }
     }
-    let left = match iter.peek().unwrap() {
-        '[' => parse_pair(iter),
-        '0'..='9' => Value::Number(iter.next().unwrap().to_digit(10).unwrap(), 0),
-        _ => panic!(),
-    };
-    if iter.next().unwrap() != ',' {
-        panic!();
+
+    fn expect(&mut self, expected: &str) -> Result<(), ParseError> {
+        let token = self.next();
+        if token == expected {
+            Ok(())
+        } else {
+            Err(ParseError::at(self.s, token, format!("'{}'", expected)))
+        }
     }
-    let right = match iter.peek().unwrap() {
-        '[' => parse_pair(iter),
-        '0'..='9' => Value::Number(iter.next().unwrap().to_digit(10).unwrap(), 0),
-        _ => panic!(),
-    };
-    if iter.next().unwrap() != ']' {
-        panic!();
+
+    fn parse_value(&mut self) -> Result<Value, ParseError> {
+        let rest = self.chars.as_str();
+        if rest.starts_with('[') && self.depth == MAX_DEPTH {
+            Err(ParseError::at(
+                self.s,
+                &rest[..1],
+                "a digit, pairs nest at most 100 deep",
+            ))
+        } else if rest.starts_with('[') {
+            self.depth += 1;
+            let pair = self.parse_pair();
+            self.depth -= 1;
+            pair
+        } else {
+            let token = self.next();
+            let n = token
+                .parse()
+                .map_err(|_| ParseError::at(self.s, token, "a digit or '['"))?;
+            Ok(Value::Number(n, 0))
+        }
+    }
+
+    fn parse_pair(&mut self) -> Result<Value, ParseError> {
+        self.expect("[")?;
+        let left = self.parse_value()?;
+        self.expect(",")?;
+        let right = self.parse_value()?;
+        self.expect("]")?;
+        Ok(Value::new_pair(left, right))
     }
-    Value::new_pair(left, right)
 }
 
 impl FromStr for Value {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut v = parse_pair(&mut s.chars().peekable());
+        let mut parser = ValueParser {
+            s,
+            chars: s.char_indices(),
+            depth: 1,
+        };
+        let mut v = parser.parse_pair()?;
+        let rest = parser.chars.as_str();
+        if !rest.is_empty() {
+            return Err(ParseError::at(s, rest, "the end of the line"));
+        }
         renumber(&mut v);
         Ok(v)
     }
@@ -129,7 +178,7 @@ fn zero_pair(tree: &mut Value, index: usize) {
         if let Value::Pair(ref n, _) = **left {
             if let Value::Number(_, i) = **n {
                 if i == index {
-                    *left = Box::new(Value::Number(0, 0));
+                    **left = Value::Number(0, 0);
                     return;
                 }
             }
@@ -137,7 +186,7 @@ fn zero_pair(tree: &mut Value, index: usize) {
         if let Value::Pair(ref n, _) = **right {
             if let Value::Number(_, i) = **n {
                 if i == index {
-                    *right = Box::new(Value::Number(0, 0));
+                    **right = Value::Number(0, 0);
                     return;
                 }
             }
@@ -184,26 +233,69 @@ fn explode(tree: &mut Value) {
     }
 }
 
-fn reduce(value: &mut Value) -> &Value {
+/// Explodes the leftmost pair nested inside four pairs, if any, once. Numbers
+/// are never split yet, so this is not a full reduction.
+pub fn reduce(value: &mut Value) -> &Value {
     explode(value);
     value
 }
 
-fn magnitude(value: Value) -> u64 {
+pub fn magnitude(value: &Value) -> u64 {
     match value {
-        Value::Number(n, _) => n as u64,
-        Value::Pair(left, right) => 3 * magnitude(*left) + 2 * magnitude(*right),
+        Value::Number(n, _) => *n as u64,
+        Value::Pair(left, right) => 3 * magnitude(left) + 2 * magnitude(right),
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day18.txt");
-    let reduced = INPUT
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .reduce(|u: Value, v| u + v)
-        .unwrap();
-    println!("solution {}", magnitude(reduced));
+fn random_value(depth: usize, rng: &mut Rng) -> Value {
+    if depth < 4 && rng.chance(1, 2) {
+        random_pair(depth + 1, rng)
+    } else {
+        Value::Number(rng.below(10) as u32, 0)
+    }
+}
+
+fn random_pair(depth: usize, rng: &mut Rng) -> Value {
+    Value::new_pair(random_value(depth, rng), random_value(depth, rng))
+}
+
+/// Reduced snailfish numbers, with pairs nested at most four deep.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| random_pair(1, rng).to_string() + "\n")
+        .collect()
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Value>;
+    type Part1 = u64;
+    type Part2 = ();
+
+    const META: Meta = Meta {
+        title: "Snailfish",
+        parts: [
+            Part::Missing("the magnitude of the sum of all the numbers"),
+            Part::Missing("the largest magnitude of the sum of any two numbers"),
+        ],
+        tests: 6,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let values = parse::lines::<Value>(input)?;
+        if values.is_empty() {
+            return Err(ParseError::end(input, "a snailfish number"));
+        }
+        Ok(values)
+    }
+
+    fn part1(values: &Self::Input) -> u64 {
+        let reduced = values.iter().cloned().reduce(|u, v| u + v).unwrap();
+        magnitude(&reduced)
+    }
+
+    fn part2(_values: &Self::Input) {}
 }
 
 #[test]
@@ -238,5 +330,14 @@ fn test_magnitude() {
     let v: Value = "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]"
         .parse()
         .unwrap();
-    assert_eq!(magnitude(v), 3488);
+    assert_eq!(magnitude(&v), 3488);
+}
+
+#[test]
+fn test_round_trip() {
+    crate::rng::check(100, |rng| {
+        let mut value = random_pair(1, rng);
+        renumber(&mut value);
+        assert_eq!(value.to_string().parse::<Value>().unwrap(), value);
+    });
 }
@@ -1,32 +1,112 @@
-use std::collections::HashSet;
 use std::str::FromStr;
 
-struct Report(i32, i32, i32);
+use crate::input;
+use crate::meta::{Meta, Part};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(PartialEq, Eq)]
+pub struct Report(pub i32, pub i32, pub i32);
 
 impl FromStr for Report {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.splitn(3, ',').map(|n| n.parse().unwrap());
-        let x = iter.next().unwrap();
-        let y = iter.next().unwrap();
-        let z = iter.next().unwrap();
-        Ok(Self(x, y, z))
+        let mut iter = s.splitn(3, ',');
+        let mut coordinate = || match iter.next() {
+            Some(n) => parse::number(s, n),
+            None => Err(ParseError::end(s, "x,y,z")),
+        };
+        Ok(Self(coordinate()?, coordinate()?, coordinate()?))
     }
 }
 
-fn make_diffs(reports: &Vec<Report>) -> HashSet<Report> {
-    let mut set = HashSet::new();
-    for (i, report) in reports.iter().enumerate() {}
-    set
+/// One of the 24 orientations: an even permutation of the axes with signs, or
+/// an odd one with a sign flipped.
+fn orient(p: [i64; 3], axes: [usize; 3], signs: [i64; 3]) -> [i64; 3] {
+    [
+        p[axes[0]] * signs[0],
+        p[axes[1]] * signs[1],
+        p[axes[2]] * signs[2],
+    ]
+}
+
+/// Scanners in a row, each sharing at least 12 beacons with its neighbors, and
+/// reporting every beacon within 1000 on each axis in its own orientation.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(1) as i64;
+    let scanners = (0..size)
+        .map(|i| [i * 1200, rng.range(-100..100), rng.range(-100..100)])
+        .collect::<Vec<[i64; 3]>>();
+    let mut beacons = Vec::new();
+    for scanner in &scanners {
+        for _ in 0..rng.range(12..20) {
+            beacons.push([
+                scanner[0] + rng.range(-900..900),
+                scanner[1] + rng.range(-800..800),
+                scanner[2] + rng.range(-800..800),
+            ]);
+        }
+        for _ in 0..12 {
+            beacons.push([
+                scanner[0] + rng.range(200..1000),
+                scanner[1] + rng.range(-800..800),
+                scanner[2] + rng.range(-800..800),
+            ]);
+        }
+    }
+    let mut reports = Vec::new();
+    for (i, scanner) in scanners.iter().enumerate() {
+        let mut axes = [0, 1, 2];
+        rng.shuffle(&mut axes);
+        let odd = (axes[0] > axes[1]) ^ (axes[0] > axes[2]) ^ (axes[1] > axes[2]);
+        let mut sign = || if rng.chance(1, 2) { 1 } else { -1 };
+        let (x, y) = (sign(), sign());
+        let signs = [x, y, if odd { -x * y } else { x * y }];
+        let mut report = format!("--- scanner {} ---\n", i);
+        for beacon in &beacons {
+            let relative = [0, 1, 2].map(|axis| beacon[axis] - scanner[axis]);
+            if relative.iter().all(|d| d.abs() <= 1000) {
+                let [x, y, z] = orient(relative, axes, signs);
+                report += &format!("{},{},{}\n", x, y, z);
+            }
+        }
+        reports.push(report);
+    }
+    reports.join("\n")
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day19.txt");
-    let reports = INPUT
-        .split("\n\n")
-        .map(|scanner| scanner.lines().map(|line| line.parse().unwrap()).collect())
-        .collect::<Vec<Vec<Report>>>();
-    let diffs: Vec<HashSet<Report>> = reports.iter().map(make_diffs).collect();
-    println!("solution {}", diffs.len());
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Vec<Report>>;
+    type Part1 = ();
+    type Part2 = ();
+
+    const META: Meta = Meta {
+        title: "Beacon Scanner",
+        parts: [
+            Part::Missing("the number of beacons"),
+            Part::Missing("the largest Manhattan distance between two scanners"),
+        ],
+        tests: 0,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input::sections(input)
+            .into_iter()
+            .map(|scanner| {
+                let (header, reports) = scanner.split_once('\n').unwrap_or((scanner, ""));
+                if !(header.starts_with("--- scanner ") && header.ends_with(" ---")) {
+                    return Err(ParseError::at(input, header, "--- scanner N ---"));
+                }
+                parse::lines(reports).map_err(|e| e.within(input, reports))
+            })
+            .collect()
+    }
+
+    fn part1(_reports: &Self::Input) {}
+
+    fn part2(_reports: &Self::Input) {}
 }
@@ -1,39 +1,51 @@
 use std::str::FromStr;
 
-enum Action {
+use crate::checked;
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Clone, Copy)]
+pub enum Action {
     Forward,
     Down,
     Up,
 }
 
-struct Command {
-    action: Action,
-    units: usize,
+pub struct Command {
+    pub action: Action,
+    pub units: usize,
 }
 
 impl FromStr for Command {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Command, Self::Err> {
-        let words = s.split_once(' ').unwrap();
-        let action = match words.0 {
-            "forward" => Action::Forward,
-            "down" => Action::Down,
-            "up" => Action::Up,
-            _ => return Err(()),
-        };
-        let units = words.1.parse().unwrap();
+        let mut cursor = Cursor::new(s, s);
+        let action = cursor.one_of(
+            &[
+                ("forward", Action::Forward),
+                ("down", Action::Down),
+                ("up", Action::Up),
+            ],
+            "forward, down or up",
+        )?;
+        let units = cursor.number()?;
+        cursor.end()?;
         Ok(Command { action, units })
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day2.txt");
-    let commands = INPUT
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<Command>>();
+pub struct Course {
+    pub position: usize,
+    pub depth: usize,
+    pub aim: usize,
+}
 
+/// Follows the commands with the aim of part 2, where the depth of part 1 is the aim.
+pub fn navigate(commands: &[Command]) -> Course {
     let mut position = 0;
     let mut depth = 0;
     let mut aim = 0;
@@ -41,21 +53,88 @@ fn main() {
     for command in commands {
         match command.action {
             Action::Forward => {
-                position += command.units;
-                depth += aim * command.units;
+                position = checked::add(position, command.units);
+                depth = checked::add(depth, checked::mul(aim, command.units));
             }
             Action::Down => {
-                aim += command.units;
+                aim = checked::add(aim, command.units);
             }
             Action::Up => {
-                aim -= command.units;
+                aim = checked::sub(aim, command.units);
             }
         }
     }
 
-    println!(
-        "horizontal position {}, depth {}, aim {}",
-        position, depth, aim
-    );
-    println!("solution {}", position * depth);
+    Course {
+        position,
+        depth,
+        aim,
+    }
+}
+
+/// Never aims above the surface, so that the aim and depth stay positive.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut aim = 0;
+    (0..size)
+        .map(|_| {
+            let units = rng.range(1..10) as usize;
+            let action = match rng.below(3) {
+                0 => "forward",
+                1 if aim >= units => {
+                    aim -= units;
+                    "up"
+                }
+                _ => {
+                    aim += units;
+                    "down"
+                }
+            };
+            format!("{} {}\n", action, units)
+        })
+        .collect()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Command>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Dive!",
+        parts: [
+            Part::Solved("the final position times the depth, following the commands as moves"),
+            Part::Solved("the same with the commands changing the aim"),
+        ],
+        tests: 1,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+    }
+
+    fn part1(commands: &Self::Input) -> usize {
+        // Without aim, the depth changes exactly as the aim does.
+        let course = navigate(commands);
+        notes::note("position", course.position);
+        notes::note("depth", course.aim);
+        checked::mul(course.position, course.aim)
+    }
+
+    fn part2(commands: &Self::Input) -> usize {
+        let course = navigate(commands);
+        notes::note("position", course.position);
+        notes::note("depth", course.depth);
+        notes::note("aim", course.aim);
+        checked::mul(course.position, course.depth)
+    }
+}
+
+#[test]
+fn test_notes() {
+    let commands = Day2::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n").unwrap();
+    let (answer, notes) = notes::collect(|| Day2::part1(&commands));
+    assert_eq!(answer, 150);
+    assert_eq!(notes, [("position", 15.into()), ("depth", 10.into())]);
 }
@@ -1,107 +1,172 @@
 use std::fmt;
 use std::str::FromStr;
 
+use crate::differential::{self, Outcome};
+use crate::frames::Color;
+use crate::grid::Grid;
+use crate::input;
+use crate::meta::{Meta, Part};
+use crate::params::{Param, Tunable};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::stepper::Simulation;
+use crate::{Answer, Solution};
+
 #[derive(Clone)]
-struct Image {
-    image: Vec<Vec<char>>,
+pub struct Image {
+    image: Grid<char>,
     algorithm: Vec<char>,
     background: bool,
 }
 
 impl Image {
     fn is_light(&self, x: i32, y: i32) -> bool {
-        if x < 1 || y < 1 || x as usize > self.image[0].len() || y as usize > self.image.len() {
+        if x < 1 || y < 1 {
             return self.background;
         }
-        match self.image[(y - 1) as usize][(x - 1) as usize] {
-            '#' => true,
-            '.' => false,
-            p => panic!("invalid pixel '{}'", p),
+        match self.image.get((x - 1) as usize, (y - 1) as usize) {
+            Some('#') => true,
+            Some('.') => false,
+            Some(p) => panic!("invalid pixel '{}'", p),
+            None => self.background,
         }
     }
 
-    fn enhance(self) -> Self {
-        let image = (0..self.image.len() + 2)
-            .map(|y| {
-                (0..self.image[0].len() + 2)
-                    .map(|x| {
-                        let mut idx = 0;
-                        if self.is_light(x as i32 - 1, y as i32 - 1) {
-                            idx += 0x100;
-                        }
-                        if self.is_light(x as i32, y as i32 - 1) {
-                            idx += 0x080;
-                        }
-                        if self.is_light(x as i32 + 1, y as i32 - 1) {
-                            idx += 0x040;
-                        }
-                        if self.is_light(x as i32 - 1, y as i32) {
-                            idx += 0x020;
-                        }
-                        if self.is_light(x as i32, y as i32) {
-                            idx += 0x010;
-                        }
-                        if self.is_light(x as i32 + 1, y as i32) {
-                            idx += 0x008;
-                        }
-                        if self.is_light(x as i32 - 1, y as i32 + 1) {
-                            idx += 0x004;
-                        }
-                        if self.is_light(x as i32, y as i32 + 1) {
-                            idx += 0x002;
-                        }
-                        if self.is_light(x as i32 + 1, y as i32 + 1) {
-                            idx += 0x001;
-                        }
-                        self.algorithm[idx]
-                    })
-                    .collect()
-            })
-            .collect();
+    pub fn enhance(self) -> Self {
+        let (width, height) = (self.image.width() + 2, self.image.height() + 2);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut idx = 0;
+                if self.is_light(x as i32 - 1, y as i32 - 1) {
+                    idx += 0x100;
+                }
+                if self.is_light(x as i32, y as i32 - 1) {
+                    idx += 0x080;
+                }
+                if self.is_light(x as i32 + 1, y as i32 - 1) {
+                    idx += 0x040;
+                }
+                if self.is_light(x as i32 - 1, y as i32) {
+                    idx += 0x020;
+                }
+                if self.is_light(x as i32, y as i32) {
+                    idx += 0x010;
+                }
+                if self.is_light(x as i32 + 1, y as i32) {
+                    idx += 0x008;
+                }
+                if self.is_light(x as i32 - 1, y as i32 + 1) {
+                    idx += 0x004;
+                }
+                if self.is_light(x as i32, y as i32 + 1) {
+                    idx += 0x002;
+                }
+                if self.is_light(x as i32 + 1, y as i32 + 1) {
+                    idx += 0x001;
+                }
+                pixels.push(self.algorithm[idx]);
+            }
+        }
         let background = if self.algorithm[0] == '#' {
             !self.background
         } else {
             false
         };
         Image {
-            image,
+            image: Grid::from_vec(width, pixels),
             algorithm: self.algorithm,
             background,
         }
     }
 
-    fn light_pixels(self) -> usize {
-        let result = self.enhance().enhance();
-        result
-            .image
-            .iter()
-            .map(|row| row.iter().filter(|p| **p == '#').count())
-            .sum()
-    }
-
-    fn super_light_pixels(self) -> usize {
+    /// The light pixels after enhancing the image `times` times.
+    pub fn light_pixels(self, times: usize) -> usize {
         let mut image = self;
-        for _ in 0..50 {
+        for _ in 0..times {
             image = image.enhance();
         }
-        image
-            .image
-            .iter()
-            .map(|row| row.iter().filter(|p| **p == '#').count())
-            .sum()
+        image.image.iter().filter(|p| **p == '#').count()
+    }
+}
+
+/// Enhances the image `times` times on a window so much larger that the
+/// pixels beyond it, always dark, can't reach the image, as a reference for
+/// [`Image::enhance`]. Returns the image and its background.
+pub fn enhance_reference(image: &Image, times: usize) -> (Grid<char>, bool) {
+    let margin = 2 * times;
+    let (width, height) = (image.image.width(), image.image.height());
+    let mut window = Grid::new(width + 2 * margin, height + 2 * margin, false);
+    for (x, y) in image.image.positions() {
+        window[(x + margin, y + margin)] = image.image[(x, y)] == '#';
+    }
+    let mut background = false;
+    for _ in 0..times {
+        let mut enhanced = window.clone();
+        for (x, y) in window.positions() {
+            let mut index = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let light = window
+                        .offset((x, y), (dx, dy))
+                        .is_some_and(|neighbor| window[neighbor]);
+                    index = index * 2 + light as usize;
+                }
+            }
+            enhanced[(x, y)] = image.algorithm[index] == '#';
+        }
+        window = enhanced;
+        background = image.algorithm[if background { 511 } else { 0 }] == '#';
+    }
+    let (width, height) = (width + 2 * times, height + 2 * times);
+    let pixels = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            if window[(x + times, y + times)] {
+                '#'
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    (Grid::from_vec(width, pixels), background)
+}
+
+pub fn differential(input: &str) -> Outcome {
+    let Ok(image) = Day20::parse(input) else {
+        return Outcome::Invalid;
+    };
+    let mut enhanced = image.clone();
+    for times in 0..4 {
+        let main = (enhanced.image.to_string(), enhanced.background);
+        let (pixels, background) = enhance_reference(&image, times);
+        let outcome = differential::compare(main, (pixels.to_string(), background));
+        if outcome != Outcome::Agree {
+            return outcome;
+        }
+        enhanced = enhanced.enhance();
     }
+    Outcome::Agree
 }
 
 impl FromStr for Image {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (algorithm, image) = s.split_once("\n\n").unwrap();
+        let (algorithm, image) = match input::sections(s)[..] {
+            [algorithm, image] => (algorithm, image),
+            [_, _, extra, ..] => return Err(ParseError::at(s, extra, "the end of the input")),
+            _ => return Err(ParseError::end(s, "a blank line before the image")),
+        };
+        if let Some(i) = algorithm.find(|c| !matches!(c, '#' | '.')) {
+            return Err(ParseError::at(s, &algorithm[i..i + 1], "'#' or '.'"));
+        }
+        if algorithm.len() != 512 {
+            return Err(ParseError::at(s, algorithm, "512 pixels"));
+        }
         let algorithm = algorithm.chars().collect();
-        let image = image
-            .lines()
-            .map(|line| line.trim_end().chars().collect())
-            .collect();
+        let image = Grid::parse(image, "'#' or '.'", |c| matches!(c, '#' | '.').then_some(c))
+            .map_err(|e| e.within(s, image))?;
         Ok(Image {
             image,
             algorithm,
@@ -110,25 +175,126 @@ impl FromStr for Image {
     }
 }
 
+/// The alternate form leaves out the algorithm.
 impl fmt::Debug for Image {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(
-            f,
-            "algorithm: {}\n",
-            self.algorithm.iter().collect::<String>()
-        )?;
-        for row in self.image.iter() {
-            writeln!(f, "{}", row.iter().collect::<String>())?;
+        if !f.alternate() {
+            writeln!(
+                f,
+                "algorithm: {}\n",
+                self.algorithm.iter().collect::<String>()
+            )?;
         }
-        Ok(())
+        write!(f, "{}", self.image)
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day20.txt");
-    let image: Image = INPUT.parse().unwrap();
-    println!("solution {}", image.clone().light_pixels());
-    println!("solution {}", image.super_light_pixels());
+/// Dark pixels, then light ones.
+pub const PALETTE: [Color; 2] = [[0, 0, 0], [255, 255, 255]];
+
+/// The image at each of the 50 enhancement steps, centered in the final size.
+pub fn frames(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError> {
+    const STEPS: usize = 50;
+    let mut image = Day20::parse(input)?;
+    let width = image.image.width() + 2 * STEPS;
+    let height = image.image.height() + 2 * STEPS;
+    for step in 0..=STEPS {
+        let margin = STEPS - step;
+        let mut pixels = Grid::new(width, height, image.background as u8);
+        for (x, y) in image.image.positions() {
+            pixels[(x + margin, y + margin)] = (image.image[(x, y)] == '#') as u8;
+        }
+        frame(&pixels);
+        if step < STEPS {
+            image = image.enhance();
+        }
+    }
+    Ok(())
+}
+
+struct Enhancing {
+    image: Image,
+    step: usize,
+}
+
+impl Simulation for Enhancing {
+    fn step(&mut self) -> bool {
+        self.image = self.image.clone().enhance();
+        self.step += 1;
+        true
+    }
+
+    fn counters(&self) -> Vec<(&'static str, Answer)> {
+        let lit = self.image.image.iter().filter(|p| **p == '#').count();
+        let background = if self.image.background { "lit" } else { "dark" };
+        vec![
+            ("step", self.step.into()),
+            ("lit pixels", lit.into()),
+            ("background", background.to_string().into()),
+        ]
+    }
+
+    fn draw(&self) -> String {
+        format!("{:#?}", self.image)
+    }
+}
+
+pub fn simulation(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Enhancing {
+        image: Day20::parse(input)?,
+        step: 0,
+    }))
+}
+
+/// Keeps the infinite background dark every other step, so that the number
+/// of light pixels stays finite.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let pixel = |rng: &mut Rng| if rng.chance(1, 2) { '#' } else { '.' };
+    let mut algorithm = (0..512).map(|_| pixel(rng)).collect::<Vec<char>>();
+    if algorithm[0] == '#' {
+        algorithm[511] = '.';
+    }
+    let mut input = algorithm.into_iter().collect::<String>();
+    input.push_str("\n\n");
+    for _ in 0..size.max(1) {
+        input.extend((0..size.max(1)).map(|_| pixel(rng)));
+        input.push('\n');
+    }
+    input
+}
+
+const PART1_TIMES: Param<usize> = Param::new("part1_times", "2", "enhancements in part 1");
+const PART2_TIMES: Param<usize> = Param::new("part2_times", "50", "enhancements in part 2");
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Image;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Trench Map",
+        parts: [
+            Part::Solved("the number of lit pixels after `part1_times` enhancements, 2 by default"),
+            Part::Solved("the same after `part2_times` enhancements, 50 by default"),
+        ],
+        tests: 2,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&PART1_TIMES, &PART2_TIMES];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(image: &Self::Input) -> usize {
+        image.clone().light_pixels(PART1_TIMES.get())
+    }
+
+    fn part2(image: &Self::Input) -> usize {
+        image.clone().light_pixels(PART2_TIMES.get())
+    }
 }
 
 #[test]
@@ -141,7 +307,7 @@ fn test_light_pixels() {
 ..#..
 ..###";
     let image: Image = INPUT.parse().unwrap();
-    assert_eq!(image.light_pixels(), 35);
+    assert_eq!(image.light_pixels(2), 35);
 }
 
 #[test]
@@ -154,5 +320,5 @@ fn test_super_light_pixels() {
 ..#..
 ..###";
     let image: Image = INPUT.parse().unwrap();
-    assert_eq!(image.super_light_pixels(), 3351);
+    assert_eq!(image.light_pixels(50), 3351);
 }
@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::params::{Param, Tunable};
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
 struct DeterministicDice {
     rolls: usize,
     last: usize,
@@ -24,7 +31,7 @@ impl Iterator for DeterministicDice {
     }
 }
 
-fn turns_to_win(positions: (usize, usize)) -> (usize, usize) {
+pub fn turns_to_win(positions: (usize, usize), target: usize) -> (usize, usize) {
     let mut dice = DeterministicDice::new();
     let mut positions = positions;
     let mut scores = (0, 0);
@@ -32,19 +39,19 @@ fn turns_to_win(positions: (usize, usize)) -> (usize, usize) {
     loop {
         positions.0 = (positions.0 + dice.next().unwrap() - 1) % 10 + 1;
         scores.0 += positions.0;
-        if scores.0 >= 1000 {
+        if scores.0 >= target {
             return (dice.rolls, scores.1);
         }
 
         positions.1 = (positions.1 + dice.next().unwrap() - 1) % 10 + 1;
         scores.1 += positions.1;
-        if scores.1 >= 1000 {
+        if scores.1 >= target {
             return (dice.rolls, scores.0);
         }
     }
 }
 
-fn simulate_universes(positions: (usize, usize)) -> usize {
+pub fn simulate_universes(positions: (usize, usize), target: usize) -> usize {
     type GameState = ((usize, usize), (usize, usize));
 
     let mut games: HashMap<GameState, usize> = HashMap::new();
@@ -64,11 +71,11 @@ fn simulate_universes(positions: (usize, usize)) -> usize {
             for player1_dice in dice_rolls {
                 let player1_position = (positions.0 + player1_dice) % 10;
                 let player1_score = scores.0 + player1_position + 1;
-                if player1_score < 21 {
+                if player1_score < target {
                     for player2_dice in dice_rolls {
                         let player2_position = (positions.1 + player2_dice) % 10;
                         let player2_score = scores.1 + player2_position + 1;
-                        if player2_score < 21 {
+                        if player2_score < target {
                             let new_count = next_turn
                                 .entry((
                                     (player1_position, player2_position),
@@ -95,28 +102,74 @@ fn simulate_universes(positions: (usize, usize)) -> usize {
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day21.txt");
-    let positions = INPUT
-        .lines()
-        .map(|line| line.rsplit_once(' ').unwrap().1.parse().unwrap())
-        .collect::<Vec<usize>>();
-    let (turns, losing_score) = turns_to_win((positions[0], positions[1]));
-    println!("solution: {}", turns * losing_score);
-    println!(
-        "solution: {}",
-        simulate_universes((positions[0], positions[1]))
-    );
+/// The starting positions; the size doesn't matter.
+pub fn generate(_size: usize, rng: &mut Rng) -> String {
+    format!(
+        "Player 1 starting position: {}\nPlayer 2 starting position: {}\n",
+        rng.range(1..11),
+        rng.range(1..11)
+    )
+}
+
+const PART1_TARGET: Param<usize> = Param::new("part1_target", "1000", "winning score in part 1");
+const PART2_TARGET: Param<usize> = Param::new("part2_target", "21", "winning score in part 2");
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (usize, usize);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Dirac Dice",
+        parts: [
+            Part::Solved("the losing score times the number of rolls with the deterministic die"),
+            Part::Solved("the number of universes where the player who wins in more of them wins"),
+        ],
+        tests: 2,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&PART1_TARGET, &PART2_TARGET];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut lines = input.lines();
+        let mut position = |player: usize| {
+            let line = lines
+                .next()
+                .ok_or_else(|| ParseError::end(input, format!("player {}", player)))?;
+            let mut cursor = Cursor::new(input, line);
+            cursor.literal(&format!("Player {} starting position: ", player))?;
+            let position = cursor.token("a position")?;
+            cursor.end()?;
+            match parse::number(input, position)? {
+                n @ 1..=10 => Ok(n),
+                _ => Err(ParseError::at(input, position, "a position from 1 to 10")),
+            }
+        };
+        Ok((position(1)?, position(2)?))
+    }
+
+    fn part1(positions: &Self::Input) -> usize {
+        let (turns, losing_score) = turns_to_win(*positions, PART1_TARGET.get());
+        notes::note("rolls", turns);
+        notes::note("losing_score", losing_score);
+        turns * losing_score
+    }
+
+    fn part2(positions: &Self::Input) -> usize {
+        simulate_universes(*positions, PART2_TARGET.get())
+    }
 }
 
 #[test]
 fn test_turns_to_win() {
-    let (turns, losing_score) = turns_to_win((4, 8));
+    let (turns, losing_score) = turns_to_win((4, 8), 1000);
     assert_eq!(turns, 993);
     assert_eq!(losing_score, 745);
 }
 
 #[test]
 fn test_simulate_universes() {
-    assert_eq!(simulate_universes((4, 8)), 444356092776315);
+    assert_eq!(simulate_universes((4, 8), 21), 444356092776315);
 }
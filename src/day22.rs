@@ -1,11 +1,21 @@
+use std::collections::HashSet;
+use std::fmt;
 use std::{ops::RangeInclusive, str::FromStr};
 
-#[derive(Clone, Debug)]
-struct Cuboid {
-    positive: bool,
-    x: RangeInclusive<i64>,
-    y: RangeInclusive<i64>,
-    z: RangeInclusive<i64>,
+use crate::checked;
+use crate::differential::{self, Outcome};
+use crate::meta::{Meta, Part};
+use crate::params::{Param, Tunable};
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cuboid {
+    pub positive: bool,
+    pub x: RangeInclusive<i64>,
+    pub y: RangeInclusive<i64>,
+    pub z: RangeInclusive<i64>,
 }
 
 fn contains_range(a: &RangeInclusive<i64>, b: &RangeInclusive<i64>) -> bool {
@@ -13,36 +23,54 @@ fn contains_range(a: &RangeInclusive<i64>, b: &RangeInclusive<i64>) -> bool {
 }
 
 impl Cuboid {
-    fn contains(&self, other: &Cuboid) -> bool {
+    pub fn contains(&self, other: &Cuboid) -> bool {
         contains_range(&self.x, &other.x)
             && contains_range(&self.y, &other.y)
             && contains_range(&self.z, &other.z)
     }
 
-    fn volume(&self) -> u64 {
-        ((self.x.end() + 1 - self.x.start())
-            * (self.y.end() + 1 - self.y.start())
-            * (self.z.end() + 1 - self.z.start())) as u64
+    pub fn volume(&self) -> u64 {
+        let length = |r: &RangeInclusive<i64>| checked::add(checked::sub(*r.end(), *r.start()), 1);
+        let volume = checked::mul(
+            checked::mul(length(&self.x), length(&self.y)),
+            length(&self.z),
+        );
+        checked::cast(volume)
     }
 }
 
 impl FromStr for Cuboid {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let positive = match &s[0..3] {
-            "on " => true,
-            "off" => false,
-            _ => return Err(()),
-        };
-        let w = s.splitn(12, &['=', '.', ','][..]).collect::<Vec<&str>>();
-        let x = w[1].parse().unwrap()..=w[3].parse().unwrap();
-        let y = w[5].parse().unwrap()..=w[7].parse().unwrap();
-        let z = w[9].parse().unwrap()..=w[11].parse().unwrap();
+        let mut cursor = Cursor::new(s, s);
+        let positive = cursor.one_of(&[("on", true), ("off", false)], "on or off")?;
+        let x = cursor.range("x")?;
+        cursor.literal(",")?;
+        let y = cursor.range("y")?;
+        cursor.literal(",")?;
+        let z = cursor.range("z")?;
+        cursor.end()?;
         Ok(Cuboid { positive, x, y, z })
     }
 }
 
+impl fmt::Display for Cuboid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} x={}..{},y={}..{},z={}..{}",
+            if self.positive { "on" } else { "off" },
+            self.x.start(),
+            self.x.end(),
+            self.y.start(),
+            self.y.end(),
+            self.z.start(),
+            self.z.end()
+        )
+    }
+}
+
 #[derive(Debug)]
 struct Segments {
     inner: Vec<RangeInclusive<i64>>,
@@ -61,7 +89,9 @@ impl Segments {
         let mut ends_iter = ends.into_iter().peekable();
 
         let mut inner = Vec::new();
-        let mut start = starts_iter.next().unwrap();
+        let Some(mut start) = starts_iter.next() else {
+            return Segments { inner };
+        };
         while let Some(maybe_end) = ends_iter.peek() {
             if maybe_end < &start {
                 ends_iter.next();
@@ -93,7 +123,7 @@ impl Segments {
     }
 }
 
-fn reboot(steps: &[Cuboid]) -> u64 {
+pub fn reboot(steps: &[Cuboid]) -> u64 {
     let x_segments = Segments::new(
         &steps
             .iter()
@@ -135,7 +165,7 @@ fn reboot(steps: &[Cuboid]) -> u64 {
                     }
                 }
                 if on {
-                    count += cuboid.volume()
+                    count = checked::add(count, cuboid.volume());
                 }
             }
         }
@@ -144,14 +174,105 @@ fn reboot(steps: &[Cuboid]) -> u64 {
     count
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day22.txt");
-    let steps = INPUT
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<Cuboid>>();
-    println!("solution {}", reboot(&steps[..20]));
-    println!("solution {}", reboot(&steps));
+/// The cubes left on, turned on and off one at a time, as a reference for
+/// [`reboot`] on small inputs.
+pub fn reboot_reference(steps: &[Cuboid]) -> u64 {
+    let mut on = HashSet::new();
+    for step in steps {
+        for x in step.x.clone() {
+            for y in step.y.clone() {
+                for z in step.z.clone() {
+                    if step.positive {
+                        on.insert((x, y, z));
+                    } else {
+                        on.remove(&(x, y, z));
+                    }
+                }
+            }
+        }
+    }
+    on.len() as u64
+}
+
+pub fn differential(input: &str) -> Outcome {
+    match Day22::parse(input) {
+        Ok(steps) => differential::compare(reboot(&steps), reboot_reference(&steps)),
+        Err(_) => Outcome::Invalid,
+    }
+}
+
+fn random_range(limit: i64, rng: &mut Rng) -> RangeInclusive<i64> {
+    let start = rng.range(-limit..limit);
+    let end = start + rng.range(0..limit);
+    start..=end.min(limit)
+}
+
+/// At least 20 steps, with the first 20 in the initialization area.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(20))
+        .map(|i| {
+            let limit = if i < 20 { 50 } else { 100_000 };
+            let cuboid = Cuboid {
+                positive: i == 0 || rng.chance(2, 3),
+                x: random_range(limit, rng),
+                y: random_range(limit, rng),
+                z: random_range(limit, rng),
+            };
+            cuboid.to_string() + "\n"
+        })
+        .collect()
+}
+
+/// `size` steps close enough to the origin to count the cubes one by one.
+pub fn generate_small(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|i| {
+            let cuboid = Cuboid {
+                positive: i == 0 || rng.chance(2, 3),
+                x: random_range(5, rng),
+                y: random_range(5, rng),
+                z: random_range(5, rng),
+            };
+            cuboid.to_string() + "\n"
+        })
+        .collect()
+}
+
+const PART1_STEPS: Param<usize> = Param::new(
+    "part1_steps",
+    "20",
+    "reboot steps applied in part 1, those of the initialization procedure",
+);
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Cuboid>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    const META: Meta = Meta {
+        title: "Reactor Reboot",
+        parts: [
+            Part::Solved("the number of cubes on after the steps in the initialization area"),
+            Part::Solved("the number of cubes on after all the steps"),
+        ],
+        tests: 5,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&PART1_STEPS];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+    }
+
+    fn part1(steps: &Self::Input) -> u64 {
+        reboot(&steps[..PART1_STEPS.get().min(steps.len())])
+    }
+
+    fn part2(steps: &Self::Input) -> u64 {
+        reboot(steps)
+    }
 }
 
 #[test]
@@ -196,3 +317,29 @@ on x=-41..9,y=-7..43,z=-33..15
         .collect::<Vec<Cuboid>>();
     assert_eq!(reboot(&steps), 590784);
 }
+
+#[test]
+fn test_parse_error() {
+    let e = Day22::parse("on x=1..2,y=3..4,z=5..6\noff x=1..2,y=a..4,z=5..6\n").unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 14, "a"));
+    assert_eq!(e.expected, "a number");
+}
+
+#[test]
+fn test_round_trip() {
+    crate::rng::check(100, |rng| {
+        let cuboid = Cuboid {
+            positive: rng.chance(1, 2),
+            x: random_range(100_000, rng),
+            y: random_range(100_000, rng),
+            z: random_range(100_000, rng),
+        };
+        assert_eq!(cuboid.to_string().parse::<Cuboid>().unwrap(), cuboid);
+    });
+}
+
+#[test]
+fn test_no_steps() {
+    assert_eq!(reboot(&[]), 0);
+    assert_eq!(Day22::part1(&Day22::parse("").unwrap()), 0);
+}
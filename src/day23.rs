@@ -1,10 +1,17 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
+use crate::event;
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::search::{self, Route};
+use crate::stepper::Simulation;
+use crate::{Answer, Solution};
+
 #[derive(Copy, Clone, Eq)]
-enum Space {
+pub enum Space {
     Empty = 1,
     Amber,
     Bronze,
@@ -23,6 +30,17 @@ impl Space {
         }
     }
 
+    fn from_glyph(c: char) -> Option<Space> {
+        match c {
+            '.' => Some(Self::Empty),
+            'A' => Some(Self::Amber),
+            'B' => Some(Self::Bronze),
+            'C' => Some(Self::Copper),
+            'D' => Some(Self::Desert),
+            _ => None,
+        }
+    }
+
     fn glyph(&self) -> char {
         match self {
             Self::Empty => '.',
@@ -46,73 +64,36 @@ impl PartialEq for Space {
     }
 }
 
-impl Ord for Space {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (*other as u32).cmp(&(*self as u32))
-    }
-}
-
-impl PartialOrd for Space {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
 impl fmt::Debug for Space {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.glyph())
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Step {
-    cost: usize,
-    position: [Space; 19],
-}
+/// Draws a position the way the puzzle does, with its rows on one line unless
+/// in the alternate form.
+struct Diagram<'a>(&'a [Space; 19]);
 
-impl Ord for Step {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
-    }
-}
-
-impl PartialOrd for Step {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl fmt::Debug for Step {
+impl fmt::Debug for Diagram<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "#############")?;
-        writeln!(
-            f,
-            "#{}#",
-            self.position[0..11]
-                .iter()
-                .map(|space| space.glyph())
-                .collect::<String>()
-        )?;
-        writeln!(
-            f,
-            "###{}#{}#{}#{}###",
-            self.position[11].glyph(),
-            self.position[13].glyph(),
-            self.position[15].glyph(),
-            self.position[17].glyph()
-        )?;
-        writeln!(
-            f,
-            "  #{}#{}#{}#{}#",
-            self.position[12].glyph(),
-            self.position[14].glyph(),
-            self.position[16].glyph(),
-            self.position[18].glyph()
-        )?;
-        writeln!(f, "  #########")
+        let hallway = self.0[0..11]
+            .iter()
+            .map(|space| space.glyph())
+            .collect::<String>();
+        let glyph = |i: usize| self.0[i].glyph();
+        let (top, bottom) = (
+            format!("{}#{}#{}#{}", glyph(11), glyph(13), glyph(15), glyph(17)),
+            format!("{}#{}#{}#{}", glyph(12), glyph(14), glyph(16), glyph(18)),
+        );
+        if f.alternate() {
+            writeln!(f, "#############")?;
+            writeln!(f, "#{}#", hallway)?;
+            writeln!(f, "###{}###", top)?;
+            writeln!(f, "  #{}#", bottom)?;
+            writeln!(f, "  #########")
+        } else {
+            write!(f, "#{}# ###{}### #{}#", hallway, top, bottom)
+        }
     }
 }
 
@@ -190,56 +171,210 @@ impl Burrow {
     }
 }
 
-fn least_energy(start: &[Space; 19], goal: &[Space; 19]) -> Option<usize> {
-    let burrow = Burrow { goal: *goal };
+/// Every amphipod in its own room.
+const ORGANIZED: [Space; 19] = {
+    use Space::*;
+    [
+        Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Amber, Amber,
+        Bronze, Bronze, Copper, Copper, Desert, Desert,
+    ]
+};
 
-    let mut dist: HashMap<[Space; 19], usize> = HashMap::new();
-    let mut heap = BinaryHeap::new();
+pub fn least_energy(start: &[Space; 19], goal: &[Space; 19]) -> Option<usize> {
+    cheapest_route(start, goal).map(|route| route.cost)
+}
 
-    dist.insert(*start, 0);
-    heap.push(Step {
-        cost: 0,
-        position: *start,
-    });
+pub fn cheapest_route(
+    start: &[Space; 19],
+    goal: &[Space; 19],
+) -> Option<Route<[Space; 19], usize>> {
+    let burrow = Burrow { goal: *goal };
+    let search = search::dijkstra(
+        *start,
+        |position| {
+            let moves = burrow.valid_moves(*position);
+            event!(
+                Trace,
+                "expanded",
+                position = Diagram(position),
+                moves = moves.len()
+            );
+            moves
+        },
+        |position| position == goal,
+    );
+    notes::note("expanded", search.stats.expanded);
+    notes::note("max_frontier", search.stats.max_frontier);
+    event!(
+        Info,
+        "searched",
+        energy = search.route.as_ref().map(|route| route.cost),
+        expanded = search.stats.expanded,
+        max_frontier = search.stats.max_frontier
+    );
+    search.route
+}
 
-    while let Some(Step { cost, position }) = heap.pop() {
-        if &position == goal {
-            return Some(cost);
+struct Organizing {
+    position: [Space; 19],
+    /// The positions left along the cheapest route, last first, with the
+    /// energy of the move to each.
+    moves: Vec<([Space; 19], usize)>,
+    found: bool,
+    step: usize,
+    energy: usize,
+}
+
+impl Simulation for Organizing {
+    fn step(&mut self) -> bool {
+        match self.moves.pop() {
+            Some((position, energy)) => {
+                self.position = position;
+                self.step += 1;
+                self.energy += energy;
+                true
+            }
+            None => false,
         }
+    }
 
-        let d = dist.entry(position).or_insert(usize::MAX);
-        if &cost > d {
-            continue;
+    fn counters(&self) -> Vec<(&'static str, Answer)> {
+        if !self.found {
+            return vec![("energy", Answer::Text("no route found".to_string()))];
         }
+        vec![
+            ("step", self.step.into()),
+            ("moves left", self.moves.len().into()),
+            ("energy spent", self.energy.into()),
+        ]
+    }
+
+    fn draw(&self) -> String {
+        format!("{:#?}", Diagram(&self.position))
+    }
+}
+
+/// Follows the cheapest route from the input to the organized burrow.
+pub fn simulation(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    let position = Day23::parse(input)?;
+    let route = cheapest_route(&ORGANIZED, &position);
+    let burrow = Burrow { goal: position };
+    // The route was searched backwards, so it is followed from its end and its
+    // moves keep their energy.
+    let moves = route.as_ref().map_or(Vec::new(), |route| {
+        route
+            .path
+            .windows(2)
+            .map(|pair| {
+                let energy = burrow
+                    .valid_moves(pair[0])
+                    .into_iter()
+                    .find(|(next, _)| *next == pair[1])
+                    .map_or(0, |(_, energy)| energy);
+                (pair[0], energy)
+            })
+            .collect()
+    });
+    Ok(Box::new(Organizing {
+        position,
+        moves,
+        found: route.is_some(),
+        step: 0,
+        energy: 0,
+    }))
+}
+
+/// Two amphipods of each kind shuffled in the rooms; the size doesn't matter.
+pub fn generate(_size: usize, rng: &mut Rng) -> String {
+    let mut amphipods = "AABBCCDD".chars().collect::<Vec<char>>();
+    rng.shuffle(&mut amphipods);
+    let row = |amphipods: &[char]| {
+        amphipods
+            .iter()
+            .map(char::to_string)
+            .collect::<Vec<String>>()
+            .join("#")
+    };
+    format!(
+        "#############\n#...........#\n###{}###\n  #{}#\n  #########\n",
+        row(&amphipods[..4]),
+        row(&amphipods[4..])
+    )
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = [Space; 19];
+    type Part1 = Answer;
+    type Part2 = ();
+
+    const META: Meta = Meta {
+        title: "Amphipod",
+        parts: [
+            Part::Missing("the least energy to organize the amphipods"),
+            Part::Missing("the same with the unfolded diagram"),
+        ],
+        tests: 1,
+    };
 
-        println!("{:?}", Step { cost, position });
-
-        let moves = burrow.valid_moves(position);
-        for (next_position, next_cost) in moves {
-            let next_cost = next_cost + cost;
-            let next = Step {
-                cost: next_cost,
-                position: next_position,
-            };
-            if &next_cost < dist.get(&next_position).unwrap_or(&usize::MAX) {
-                heap.push(next);
-                dist.insert(next_position, next_cost);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut position = [Space::Empty; 19];
+        // The rooms are full, with two of each amphipod.
+        let mut counts = [0; 4];
+        let mut lines = input.lines().skip(2);
+        for depth in 0..2 {
+            let line = lines
+                .next()
+                .ok_or_else(|| ParseError::end(input, "a row of rooms"))?;
+            let rooms = line.char_indices().filter(|(_, c)| *c != '#' && *c != ' ');
+            let mut found = 0;
+            for (i, c) in rooms {
+                let token = &line[i..i + c.len_utf8()];
+                if found == 4 {
+                    return Err(ParseError::at(input, token, "'#'"));
+                }
+                let space = match Space::from_glyph(c) {
+                    Some(Space::Empty) | None => {
+                        return Err(ParseError::at(input, token, "an amphipod"));
+                    }
+                    Some(space) => space,
+                };
+                let count = &mut counts[space as usize - Space::Amber as usize];
+                if *count == 2 {
+                    return Err(ParseError::at(input, token, "at most two of each amphipod"));
+                }
+                *count += 1;
+                position[11 + 2 * found + depth] = space;
+                found += 1;
+            }
+            if found < 4 {
+                return Err(ParseError::at(input, line, "4 rooms"));
             }
         }
+        Ok(position)
     }
 
-    None
+    fn part1(goal: &Self::Input) -> Answer {
+        // Moves are reversible, so search from the organized burrow back to the input.
+        match least_energy(&ORGANIZED, goal) {
+            Some(energy) => energy.into(),
+            None => Answer::Text("no way to organize the amphipods".to_string()),
+        }
+    }
+
+    fn part2(_goal: &Self::Input) {}
 }
 
-fn main() {
-    use Space::*;
-    let start = [
-        Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Amber, Amber,
-        Bronze, Bronze, Copper, Copper, Desert, Desert,
-    ];
-    let goal = [
-        Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Desert,
-        Bronze, Desert, Amber, Copper, Amber, Bronze, Copper,
-    ];
-    println!("solution {}", least_energy(&start, &goal).unwrap());
+#[test]
+fn test_parse() {
+    let burrow = "#############\n#...........#\n###B#C#B#D###\n  #A#D#C#A#\n  #########\n";
+    assert!(Day23::parse(burrow).is_ok());
+    let e = Day23::parse(&burrow.replace("C#A", "A#A")).unwrap_err();
+    assert_eq!(
+        (e.line, e.column, e.expected.as_str()),
+        (4, 10, "at most two of each amphipod")
+    );
+    let e = Day23::parse(&burrow.replace("#D#C", "#D#.")).unwrap_err();
+    assert_eq!((e.line, e.expected.as_str()), (4, "an amphipod"));
 }
@@ -1,7 +1,14 @@
+use std::fmt;
 use std::str::FromStr;
 
-#[derive(Copy, Clone)]
-enum Operand {
+use crate::event;
+use crate::meta::{Meta, Part};
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operand {
     W,
     X,
     Y,
@@ -10,7 +17,7 @@ enum Operand {
 }
 
 impl FromStr for Operand {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -18,13 +25,45 @@ impl FromStr for Operand {
             "x" => Ok(Operand::X),
             "y" => Ok(Operand::Y),
             "z" => Ok(Operand::Z),
-            _ => Ok(Operand::Literal(s.parse().unwrap())),
+            _ => parse::number(s, s)
+                .map(Operand::Literal)
+                .map_err(|e| ParseError {
+                    expected: "a register or a number".to_string(),
+                    ..e
+                }),
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::W => write!(f, "w"),
+            Operand::X => write!(f, "x"),
+            Operand::Y => write!(f, "y"),
+            Operand::Z => write!(f, "z"),
+            Operand::Literal(n) => write!(f, "{}", n),
         }
     }
 }
 
-#[derive(Copy, Clone)]
-enum Instruction {
+fn operand(cursor: &mut Cursor) -> Result<Operand, ParseError> {
+    let token = cursor.token("an operand")?;
+    token
+        .parse()
+        .map_err(|e: ParseError| cursor.error(token, e.expected))
+}
+
+fn register(cursor: &mut Cursor) -> Result<Operand, ParseError> {
+    let token = cursor.token("a register")?;
+    match token.parse() {
+        Ok(Operand::Literal(_)) | Err(_) => Err(cursor.error(token, "a register")),
+        Ok(register) => Ok(register),
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Instruction {
     Inp(Operand),
     Add(Operand, Operand),
     Mul(Operand, Operand),
@@ -34,34 +73,36 @@ enum Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split_ascii_whitespace();
-        match iter.next().unwrap() {
-            "inp" => Ok(Instruction::Inp(iter.next().unwrap().parse().unwrap())),
-            "add" => Ok(Instruction::Add(
-                iter.next().unwrap().parse().unwrap(),
-                iter.next().unwrap().parse().unwrap(),
-            )),
-            "mul" => Ok(Instruction::Mul(
-                iter.next().unwrap().parse().unwrap(),
-                iter.next().unwrap().parse().unwrap(),
-            )),
-            "div" => Ok(Instruction::Div(
-                iter.next().unwrap().parse().unwrap(),
-                iter.next().unwrap().parse().unwrap(),
-            )),
-            "mod" => Ok(Instruction::Mod(
-                iter.next().unwrap().parse().unwrap(),
-                iter.next().unwrap().parse().unwrap(),
-            )),
-            "eql" => Ok(Instruction::Eql(
-                iter.next().unwrap().parse().unwrap(),
-                iter.next().unwrap().parse().unwrap(),
-            )),
-            _ => Err(()),
-        }
+        let c = &mut Cursor::new(s, s);
+        let op = c.token("an instruction")?;
+        let instruction = match op {
+            "inp" => Instruction::Inp(register(c)?),
+            "add" => Instruction::Add(register(c)?, operand(c)?),
+            "mul" => Instruction::Mul(register(c)?, operand(c)?),
+            "div" => Instruction::Div(register(c)?, operand(c)?),
+            "mod" => Instruction::Mod(register(c)?, operand(c)?),
+            "eql" => Instruction::Eql(register(c)?, operand(c)?),
+            _ => return Err(c.error(op, "an instruction")),
+        };
+        c.end()?;
+        Ok(instruction)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, a, b) = match *self {
+            Instruction::Inp(a) => return write!(f, "inp {}", a),
+            Instruction::Add(a, b) => ("add", a, b),
+            Instruction::Mul(a, b) => ("mul", a, b),
+            Instruction::Div(a, b) => ("div", a, b),
+            Instruction::Mod(a, b) => ("mod", a, b),
+            Instruction::Eql(a, b) => ("eql", a, b),
+        };
+        write!(f, "{} {} {}", op, a, b)
     }
 }
 
@@ -147,6 +188,42 @@ impl ArithmeticLogicUnit {
     }
 }
 
+/// Runs a program on the given inputs, in the order they are read, and
+/// returns the `z` register.
+pub fn run(instructions: &[Instruction], inputs: &[i64]) -> i64 {
+    let inputs = inputs.iter().rev().copied().collect::<Vec<i64>>();
+    let mut alu = ArithmeticLogicUnit::new(&inputs);
+    for instruction in instructions {
+        alu.eval(*instruction);
+        event!(
+            Trace,
+            "executed",
+            instruction = format_args!("{}", instruction),
+            w = alu.w,
+            x = alu.x,
+            y = alu.y,
+            z = alu.z
+        );
+    }
+    alu.z
+}
+
+pub fn largest_model_number(instructions: &[Instruction]) -> String {
+    for digits in ModelNumber::new() {
+        let digits = digits.iter().rev().copied().collect::<Vec<i64>>();
+        let z = run(instructions, &digits);
+        event!(Debug, "checked", digits = digits, z = z);
+        if z == 0 {
+            event!(Info, "found", digits = digits);
+            return digits
+                .iter()
+                .map(|d| char::from_digit(*d as u32, 10).unwrap())
+                .collect::<String>();
+        }
+    }
+    unreachable!()
+}
+
 struct ModelNumber {
     digits: [i64; 14],
 }
@@ -175,30 +252,75 @@ impl Iterator for ModelNumber {
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day24.txt");
-    let instructions = INPUT
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect::<Vec<Instruction>>();
-    let model_number = ModelNumber::new();
-    for digits in model_number {
-        let mut alu = ArithmeticLogicUnit::new(&digits);
-        for instruction in instructions.iter() {
-            alu.eval(*instruction);
+/// A MONAD program of 14 digit checks, where each check that shrinks `z` is
+/// paired with an earlier one that grew it, with offsets that some digits satisfy.
+pub fn generate(_size: usize, rng: &mut Rng) -> String {
+    let mut pending = Vec::new();
+    let mut pushes = 7;
+    let mut program = String::new();
+    for _ in 0..14 {
+        let (divisor, check, offset) = if pushes > 0 && (pending.is_empty() || rng.chance(1, 2)) {
+            pushes -= 1;
+            let offset = rng.range(1..17);
+            pending.push(offset);
+            (1, rng.range(10..17), offset)
+        } else {
+            let pushed = pending.pop().unwrap();
+            let check = rng.range(-8..9) - pushed;
+            (26, check, rng.range(1..17))
+        };
+        program += &format!(
+            "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+             mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\n\
+             mul y x\nadd z y\n",
+            divisor, check, offset
+        );
+    }
+    program
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<Instruction>;
+    type Part1 = String;
+    type Part2 = ();
+
+    const META: Meta = Meta {
+        title: "Arithmetic Logic Unit",
+        parts: [
+            Part::Solved("the largest model number accepted by MONAD"),
+            Part::Missing("the smallest model number accepted by MONAD"),
+        ],
+        tests: 4,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let instructions: Vec<Instruction> = parse::lines(input)?;
+        // The search feeds the program one 14-digit model number.
+        let mut inputs = input
+            .lines()
+            .zip(&instructions)
+            .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)));
+        if let Some((line, _)) = inputs.nth(14) {
+            return Err(ParseError::at(input, line, "at most 14 inp instructions"));
         }
-        if alu.z == 0 {
-            println!(
-                "solution {}",
-                digits
-                    .iter()
-                    .rev()
-                    .map(|d| char::from_digit(*d as u32, 10).unwrap())
-                    .collect::<String>()
-            );
-            break;
+        if instructions
+            .iter()
+            .filter(|i| matches!(i, Instruction::Inp(_)))
+            .count()
+            < 14
+        {
+            return Err(ParseError::end(input, "14 inp instructions"));
         }
+        Ok(instructions)
     }
+
+    fn part1(instructions: &Self::Input) -> String {
+        largest_model_number(instructions)
+    }
+
+    fn part2(_instructions: &Self::Input) {}
 }
 
 #[test]
@@ -218,3 +340,37 @@ fn test_equals_triple() {
     alu.eval(Instruction::Eql(Operand::Z, Operand::X));
     assert_eq!(alu.z, 1);
 }
+
+#[test]
+fn test_round_trip() {
+    crate::rng::check(100, |rng| {
+        let registers = [Operand::W, Operand::X, Operand::Y, Operand::Z];
+        let a = *rng.choose(&registers);
+        let b = if rng.chance(1, 2) {
+            *rng.choose(&registers)
+        } else {
+            Operand::Literal(rng.range(-1000..1000))
+        };
+        let instruction = match rng.below(6) {
+            0 => Instruction::Inp(a),
+            1 => Instruction::Add(a, b),
+            2 => Instruction::Mul(a, b),
+            3 => Instruction::Div(a, b),
+            4 => Instruction::Mod(a, b),
+            _ => Instruction::Eql(a, b),
+        };
+        assert_eq!(
+            instruction.to_string().parse::<Instruction>().unwrap(),
+            instruction
+        );
+    });
+}
+
+#[test]
+fn test_digit_count() {
+    assert!(Day24::parse("").is_err());
+    let program = "inp w\nadd z w\n".repeat(15);
+    let error = Day24::parse(&program).unwrap_err();
+    assert_eq!((error.line, error.text.as_str()), (29, "inp w"));
+    assert!(Day24::parse(&program[..program.len() - 14]).is_ok());
+}
@@ -1,18 +1,27 @@
 use std::fmt;
 use std::str::FromStr;
 
+use crate::frames::Color;
+use crate::grid::Grid;
+use crate::meta::{Meta, Part};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::stepper::Simulation;
+use crate::{Answer, Solution};
+
 #[derive(Copy, Clone, PartialEq)]
 enum Herd {
     East,
     South,
 }
 
-struct SeaCucumberMap {
-    inner: Vec<Vec<Option<Herd>>>,
+#[derive(Clone)]
+pub struct SeaCucumberMap {
+    inner: Grid<Option<Herd>>,
 }
 
 impl SeaCucumberMap {
-    fn stops_moving(&mut self) -> usize {
+    pub fn stops_moving(&mut self) -> usize {
         let mut steps = 0;
         while self.step() {
             steps += 1;
@@ -20,93 +29,160 @@ impl SeaCucumberMap {
         steps + 1
     }
 
-    fn step(&mut self) -> bool {
-        let shifted: Vec<Vec<Option<Herd>>> =
-            (0..self.inner.len()).map(|i| self.shift_right(i)).collect();
-        let new_map = (0..self.inner.len())
-            .map(|i| self.shift_down(i, &shifted))
-            .collect();
+    pub fn step(&mut self) -> bool {
+        let shifted = shift(&self.inner, Herd::East, (1, 0));
+        let new_map = shift(&shifted, Herd::South, (0, 1));
         let changed = self.inner != new_map;
         self.inner = new_map;
         changed
     }
+}
 
-    fn shift_right(&self, i: usize) -> Vec<Option<Herd>> {
-        let row = &self.inner[i];
-        let mut new_row = Vec::with_capacity(row.len());
-        for (i, loc) in row.iter().enumerate() {
-            let left = row[(i + row.len() - 1) % row.len()];
-            let right = row[(i + row.len() + 1) % row.len()];
-            new_row.push(match (left, loc, right) {
-                (Some(Herd::East), None, _) => left,
-                (_, Some(Herd::East), None) => None,
-                _ => *loc,
-            });
-        }
-        new_row
-    }
-
-    fn shift_down(&self, i: usize, shifted: &[Vec<Option<Herd>>]) -> Vec<Option<Herd>> {
-        let height = self.inner.len();
-        let row = &shifted[i];
-        let above_row = &shifted[(i + height - 1) % height];
-        let below_row = &shifted[(i + height + 1) % height];
-        let mut new_row = Vec::with_capacity(row.len());
-        for (above, (loc, below)) in above_row.iter().zip(row.iter().zip(below_row)) {
-            new_row.push(match (*above, *loc, below) {
-                (Some(Herd::South), None, _) => *above,
-                (_, Some(Herd::South), None) => None,
-                _ => *loc,
-            });
+fn shift(map: &Grid<Option<Herd>>, herd: Herd, offset: (isize, isize)) -> Grid<Option<Herd>> {
+    let mut new_map = map.clone();
+    for position in map.positions() {
+        if map[position] == Some(herd) {
+            let next = map.offset(position, offset).unwrap();
+            if map[next].is_none() {
+                new_map[next] = Some(herd);
+                new_map[position] = None;
+            }
         }
-        new_row
     }
+    new_map
 }
 
 impl FromStr for SeaCucumberMap {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let inner = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        '>' => Some(Herd::East),
-                        'v' => Some(Herd::South),
-                        '.' => None,
-                        _ => panic!(),
-                    })
-                    .collect()
-            })
-            .collect();
-        Ok(SeaCucumberMap { inner })
+        let inner = Grid::parse(s, "'>', 'v' or '.'", |c| match c {
+            '>' => Some(Some(Herd::East)),
+            'v' => Some(Some(Herd::South)),
+            '.' => Some(None),
+            _ => None,
+        })?;
+        Ok(SeaCucumberMap {
+            inner: inner.wrapping(),
+        })
     }
 }
 
 impl fmt::Debug for SeaCucumberMap {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in self.inner.iter() {
-            writeln!(
-                f,
-                "{}",
-                row.iter()
-                    .map(|c| match c {
-                        Some(Herd::East) => '>',
-                        Some(Herd::South) => 'v',
-                        None => '.',
-                    })
-                    .collect::<String>()
-            )?;
-        }
-        Ok(())
+        let glyphs = self.inner.map(|c| match c {
+            Some(Herd::East) => '>',
+            Some(Herd::South) => 'v',
+            None => '.',
+        });
+        write!(f, "{}", glyphs)
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day25.txt");
-    let mut map: SeaCucumberMap = INPUT.parse().unwrap();
-    println!("solution {}", map.stops_moving());
+/// The sea floor, then the east-facing and south-facing herds.
+pub const PALETTE: [Color; 3] = [[0, 24, 64], [250, 140, 30], [60, 200, 90]];
+
+/// The herds at each step, until they stop moving.
+pub fn frames(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError> {
+    let mut map = Day25::parse(input)?;
+    let draw = |map: &SeaCucumberMap| {
+        map.inner.map(|cell| match cell {
+            None => 0,
+            Some(Herd::East) => 1,
+            Some(Herd::South) => 2,
+        })
+    };
+    frame(&draw(&map));
+    while map.step() {
+        frame(&draw(&map));
+    }
+    Ok(())
+}
+
+struct Cucumbers {
+    map: SeaCucumberMap,
+    step: usize,
+    moved: usize,
+}
+
+impl Simulation for Cucumbers {
+    fn step(&mut self) -> bool {
+        let before = self.map.inner.clone();
+        let changed = self.map.step();
+        self.step += 1;
+        // Each move empties one place and fills another.
+        self.moved = before
+            .iter()
+            .zip(self.map.inner.iter())
+            .filter(|(a, b)| a != b)
+            .count()
+            / 2;
+        changed
+    }
+
+    fn counters(&self) -> Vec<(&'static str, Answer)> {
+        vec![("step", self.step.into()), ("moved", self.moved.into())]
+    }
+
+    fn draw(&self) -> String {
+        format!("{:?}", self.map)
+    }
+}
+
+pub fn simulation(input: &str) -> Result<Box<dyn Simulation>, ParseError> {
+    Ok(Box::new(Cucumbers {
+        map: Day25::parse(input)?,
+        step: 0,
+        moved: 0,
+    }))
+}
+
+/// Random herds with a full column of south-facing and a full row of
+/// east-facing sea cucumbers, which never move and keep the others from
+/// going around forever.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(2);
+    let (column, row) = (rng.index(size), rng.index(size));
+    let cells = (0..size * size)
+        .map(|i| {
+            let (x, y) = (i % size, i / size);
+            if x == column {
+                'v'
+            } else if y == row {
+                '>'
+            } else {
+                *rng.choose(&['>', 'v', '.'])
+            }
+        })
+        .collect();
+    Grid::from_vec(size, cells).to_string()
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = SeaCucumberMap;
+    type Part1 = usize;
+    type Part2 = ();
+
+    const META: Meta = Meta {
+        title: "Sea Cucumber",
+        parts: [
+            Part::Solved("the first step on which no sea cucumber moves"),
+            Part::NoPuzzle,
+        ],
+        tests: 1,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> usize {
+        map.clone().stops_moving()
+    }
+
+    fn part2(_map: &Self::Input) {}
 }
 
 #[test]
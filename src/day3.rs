@@ -1,22 +1,31 @@
-struct Diagnostics<const N: usize> {
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::{Answer, Solution};
+
+pub struct Diagnostics<const N: usize> {
     report: Vec<String>,
 }
 
 impl<const N: usize> Diagnostics<N> {
-    fn gamma_rate(&self) -> u32 {
+    pub fn gamma_rate(&self) -> u32 {
         u32::from_str_radix(&self.most_common_bits(), 2).unwrap()
     }
 
-    fn epsilon_rate(&self) -> u32 {
+    pub fn epsilon_rate(&self) -> u32 {
         0xfff ^ self.gamma_rate()
     }
 
-    fn oxygen_generator_rating(&self) -> u32 {
-        u32::from_str_radix(&self.most_common_value(), 2).unwrap()
+    /// The rating, unless filtering the report leaves no value.
+    pub fn oxygen_generator_rating(&self) -> Option<u32> {
+        Some(u32::from_str_radix(&self.most_common_value()?, 2).unwrap())
     }
 
-    fn co2_scrubber_rating(&self) -> u32 {
-        u32::from_str_radix(&self.least_common_value(), 2).unwrap()
+    /// The rating, unless filtering the report leaves no value, as when every
+    /// value has the same bit somewhere.
+    pub fn co2_scrubber_rating(&self) -> Option<u32> {
+        Some(u32::from_str_radix(&self.least_common_value()?, 2).unwrap())
     }
 
     fn count_bit(&self, values: &[String], bit: usize) -> (usize, usize) {
@@ -63,54 +72,104 @@ impl<const N: usize> Diagnostics<N> {
             .collect()
     }
 
-    fn most_common_value(&self) -> String {
-        (0..N)
-            .fold(self.report.clone(), |r, i| {
-                self.filter_values(r, i, |zeros, ones| ones >= zeros)
-            })
+    fn filter_report(&self, predicate: impl Fn(usize, usize) -> bool + Copy) -> Vec<String> {
+        (0..N).fold(self.report.clone(), |r, i| {
+            self.filter_values(r, i, predicate)
+        })
+    }
+
+    fn most_common_value(&self) -> Option<String> {
+        self.filter_report(|zeros, ones| ones >= zeros)
             .first()
-            .unwrap()
-            .clone()
+            .cloned()
     }
 
-    fn least_common_value(&self) -> String {
-        (0..N)
-            .fold(self.report.clone(), |r, i| {
-                self.filter_values(r, i, |zeros, ones| ones < zeros)
-            })
+    fn least_common_value(&self) -> Option<String> {
+        self.filter_report(|zeros, ones| ones < zeros)
             .first()
-            .unwrap()
-            .clone()
+            .cloned()
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day3.txt");
-    let report = INPUT
-        .lines()
-        .map(|line| line.to_string())
-        .collect::<Vec<String>>();
-    let diagnostics = Diagnostics::<12> { report };
-
-    println!(
-        "gamma rate {} epsilon rate {}",
-        diagnostics.gamma_rate(),
-        diagnostics.epsilon_rate()
-    );
-    println!(
-        "solution {}",
-        diagnostics.gamma_rate() * diagnostics.epsilon_rate()
-    );
+/// Distinct values, picked again until both ratings filter down to a value.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.clamp(1, 1 << 12);
+    loop {
+        let mut values = (0..1 << 12).collect::<Vec<u32>>();
+        rng.shuffle(&mut values);
+        let diagnostics = Diagnostics::<12> {
+            report: values[..size]
+                .iter()
+                .map(|value| format!("{:012b}", value))
+                .collect(),
+        };
+        if !diagnostics
+            .filter_report(|zeros, ones| ones >= zeros)
+            .is_empty()
+            && !diagnostics
+                .filter_report(|zeros, ones| ones < zeros)
+                .is_empty()
+        {
+            return diagnostics.report.join("\n") + "\n";
+        }
+    }
+}
 
-    println!(
-        "oxygen generator rating {} CO2 scrubber rating {}",
-        diagnostics.oxygen_generator_rating(),
-        diagnostics.co2_scrubber_rating()
-    );
-    println!(
-        "solution {}",
-        diagnostics.oxygen_generator_rating() * diagnostics.co2_scrubber_rating()
-    );
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Diagnostics<12>;
+    type Part1 = u32;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Binary Diagnostic",
+        parts: [
+            Part::Solved("the power consumption, from the most and least common bits"),
+            Part::Solved(
+                "the life support rating, from the oxygen generator and CO2 scrubber ratings",
+            ),
+        ],
+        tests: 2,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let report = input
+            .lines()
+            .map(|line| {
+                if let Some(i) = line.find(|c| c != '0' && c != '1') {
+                    Err(ParseError::at(input, &line[i..i + 1], "a bit"))
+                } else if line.len() != 12 {
+                    Err(ParseError::at(input, line, "12 bits"))
+                } else {
+                    Ok(line.to_string())
+                }
+            })
+            .collect::<Result<Vec<String>, _>>()?;
+        if report.is_empty() {
+            return Err(ParseError::end(input, "12 bits"));
+        }
+        Ok(Diagnostics::<12> { report })
+    }
+
+    fn part1(diagnostics: &Self::Input) -> u32 {
+        let (gamma, epsilon) = (diagnostics.gamma_rate(), diagnostics.epsilon_rate());
+        notes::note("gamma", gamma);
+        notes::note("epsilon", epsilon);
+        gamma * epsilon
+    }
+
+    fn part2(diagnostics: &Self::Input) -> Answer {
+        let (Some(oxygen), Some(co2)) = (
+            diagnostics.oxygen_generator_rating(),
+            diagnostics.co2_scrubber_rating(),
+        ) else {
+            return Answer::Text("the report filters down to no value".to_string());
+        };
+        notes::note("oxygen_generator_rating", oxygen);
+        notes::note("co2_scrubber_rating", co2);
+        (oxygen * co2).into()
+    }
 }
 
 #[test]
@@ -130,5 +189,17 @@ fn oxygen_generator_rating() {
         "01010".to_string(),
     ];
     let diagnostics = Diagnostics::<5> { report };
-    assert_eq!(diagnostics.most_common_value(), "10111");
+    assert_eq!(diagnostics.most_common_value().as_deref(), Some("10111"));
+}
+
+#[test]
+fn test_no_rating() {
+    assert!(Day3::parse("").is_err());
+    // Every value starts with 1, so none is left with the least common bit.
+    let diagnostics = Day3::parse("100000000000\n110000000000\n").unwrap();
+    assert_eq!(diagnostics.co2_scrubber_rating(), None);
+    assert_eq!(
+        Day3::part2(&diagnostics),
+        Answer::Text("the report filters down to no value".to_string())
+    );
 }
@@ -1,17 +1,25 @@
-use std::num::ParseIntError;
 use std::str::FromStr;
 
+use crate::input;
+use crate::meta::{Meta, Part};
+use crate::notes;
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::{Answer, Solution};
+
+#[derive(Clone)]
 struct Square {
     number: u8,
     marked: bool,
 }
 
-struct Board {
+#[derive(Clone)]
+pub struct Board {
     grid: Vec<Vec<Square>>,
 }
 
 impl Board {
-    fn mark(&mut self, number: u8) {
+    pub fn mark(&mut self, number: u8) {
         for row in &mut self.grid {
             if let Some(square) = row.iter_mut().find(|square| square.number == number) {
                 square.marked = true;
@@ -19,7 +27,7 @@ impl Board {
         }
     }
 
-    fn is_winning(&self) -> bool {
+    pub fn is_winning(&self) -> bool {
         for row in &self.grid {
             if row.iter().all(|s| s.marked) {
                 return true;
@@ -33,7 +41,8 @@ impl Board {
         false
     }
 
-    fn score(&self) -> u32 {
+    /// The sum of the unmarked numbers.
+    pub fn score(&self) -> u32 {
         self.grid.iter().fold(0_u32, |acc, row| {
             acc + row
                 .iter()
@@ -44,80 +53,149 @@ impl Board {
 }
 
 impl FromStr for Board {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Board, Self::Err> {
         let grid = s
             .lines()
             .map(|line| {
-                line.split_whitespace()
-                    .map(|s| Square {
-                        number: s.parse().unwrap(),
-                        marked: false,
+                let row = line
+                    .split_whitespace()
+                    .map(|n| {
+                        Ok(Square {
+                            number: parse::number(s, n)?,
+                            marked: false,
+                        })
                     })
-                    .collect()
+                    .collect::<Result<Vec<Square>, ParseError>>()?;
+                if row.len() != 5 {
+                    return Err(ParseError::at(s, line, "5 numbers"));
+                }
+                Ok(row)
             })
-            .collect::<Vec<Vec<Square>>>();
+            .collect::<Result<Vec<Vec<Square>>, _>>()?;
+        if grid.len() != 5 {
+            return Err(ParseError::end(s, "5 rows"));
+        }
         Ok(Board { grid })
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day4.txt");
-    solve_part1(INPUT);
-    solve_part2(INPUT);
+pub struct Bingo {
+    pub drawn_numbers: Vec<u8>,
+    pub boards: Vec<Board>,
 }
 
-fn solve_part1(input: &str) {
-    let mut blocks = input.split("\n\n");
-    let drawn_numbers = blocks
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse().unwrap());
-    let mut boards: Vec<Board> = blocks.map(|b| b.parse().unwrap()).collect();
-
-    for number in drawn_numbers {
-        for board in &mut boards {
-            board.mark(number);
-            if board.is_winning() {
-                println!(
-                    "board wins after number {}, score {}",
-                    number,
-                    board.score()
-                );
-                println!("solution {}", board.score() * number as u32);
-                return;
-            }
+/// Draws every number, so that every board eventually wins.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut numbers = (0..100).collect::<Vec<u8>>();
+    rng.shuffle(&mut numbers);
+    let mut input = numbers
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<String>>()
+        .join(",");
+    input.push('\n');
+    for _ in 0..size.max(1) {
+        rng.shuffle(&mut numbers);
+        input.push('\n');
+        for row in numbers[..25].chunks(5) {
+            let row = row.iter().map(|n| format!("{:>2}", n));
+            input += &(row.collect::<Vec<String>>().join(" ") + "\n");
         }
     }
+    input
 }
 
-fn solve_part2(input: &str) {
-    let mut blocks = input.split("\n\n");
-    let drawn_numbers = blocks
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|n| n.parse().unwrap());
-    let mut boards: Vec<Board> = blocks.map(|b| b.parse().unwrap()).collect();
-    let mut winning_boards = vec![false; boards.len()];
-
-    for number in drawn_numbers {
-        for (i, board) in boards.iter_mut().enumerate() {
-            board.mark(number);
-            if board.is_winning() {
-                winning_boards[i] = true;
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Bingo;
+    type Part1 = Answer;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Giant Squid",
+        parts: [
+            Part::Solved("the score of the first bingo board to win"),
+            Part::Solved("the score of the last board to win"),
+        ],
+        tests: 1,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut blocks = input::sections(input).into_iter();
+        let drawn_numbers = blocks
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(|n| parse::number(input, n))
+            .collect::<Result<_, _>>()?;
+        let boards = blocks
+            .map(|b| b.parse().map_err(|e: ParseError| e.within(input, b)))
+            .collect::<Result<_, _>>()?;
+        Ok(Bingo {
+            drawn_numbers,
+            boards,
+        })
+    }
+
+    fn part1(bingo: &Self::Input) -> Answer {
+        let mut boards = bingo.boards.clone();
+
+        for &number in &bingo.drawn_numbers {
+            for board in &mut boards {
+                board.mark(number);
+                if board.is_winning() {
+                    notes::note("number", number as u32);
+                    notes::note("score", board.score());
+                    return (board.score() * number as u32).into();
+                }
             }
-            if winning_boards.iter().all(|b| *b) {
-                println!(
-                    "board wins after number {}, score {}",
-                    number,
-                    board.score()
-                );
-                println!("solution {}", board.score() * number as u32);
-                return;
+        }
+        Answer::Text("no board wins".to_string())
+    }
+
+    fn part2(bingo: &Self::Input) -> Answer {
+        let mut boards = bingo.boards.clone();
+        let mut winning_boards = vec![false; boards.len()];
+
+        for &number in &bingo.drawn_numbers {
+            for (i, board) in boards.iter_mut().enumerate() {
+                board.mark(number);
+                if board.is_winning() {
+                    winning_boards[i] = true;
+                }
+                if winning_boards.iter().all(|b| *b) {
+                    notes::note("number", number as u32);
+                    notes::note("score", board.score());
+                    return (board.score() * number as u32).into();
+                }
             }
         }
+        Answer::Text("some boards never win".to_string())
     }
 }
+
+#[test]
+fn test_unfinished_draws() {
+    let board = (0..25).map(|n| format!("{:2} ", n)).collect::<Vec<_>>();
+    let board = board
+        .chunks(5)
+        .map(|row| row.concat() + "\n")
+        .collect::<String>();
+    let bingo = Day4::parse(&format!("0,6,12,18\n\n{}", board)).unwrap();
+    assert_eq!(
+        Day4::part1(&bingo),
+        Answer::Text("no board wins".to_string())
+    );
+    // The first row wins on the first board, with 290 unmarked, but not on
+    // the second one, where 4 is replaced by 99.
+    let other = board.replace(" 4 ", "99 ");
+    let bingo = Day4::parse(&format!("0,1,2,3,4\n\n{}\n{}", board, other)).unwrap();
+    assert_eq!(Day4::part1(&bingo), Answer::Number(290 * 4));
+    assert_eq!(
+        Day4::part2(&bingo),
+        Answer::Text("some boards never win".to_string())
+    );
+}
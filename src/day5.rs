@@ -1,14 +1,25 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::num::ParseIntError;
 use std::str::FromStr;
 
-struct Vent {
-    from: (u32, u32),
-    to: (u32, u32),
+use crate::meta::{Meta, Part};
+use crate::parse::{self, Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+#[derive(Debug, PartialEq)]
+pub struct Vent {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+}
+
+impl Vent {
+    pub fn is_straight(&self) -> bool {
+        self.from.0 == self.to.0 || self.from.1 == self.to.1
+    }
 }
 
-impl IntoIterator for Vent {
+impl IntoIterator for &Vent {
     type Item = (u32, u32);
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
@@ -40,24 +51,15 @@ impl IntoIterator for Vent {
 }
 
 impl FromStr for Vent {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Vent, Self::Err> {
-        let (from, to) = s
-            .split_once(" -> ")
-            .map(|(from, to)| {
-                (
-                    from.split_once(',')
-                        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap())),
-                    to.split_once(',')
-                        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap())),
-                )
-            })
-            .unwrap();
-        Ok(Vent {
-            from: from.unwrap(),
-            to: to.unwrap(),
-        })
+        let mut cursor = Cursor::new(s, s);
+        let from = cursor.pair(",")?;
+        cursor.literal(" -> ")?;
+        let to = cursor.pair(",")?;
+        cursor.end()?;
+        Ok(Vent { from, to })
     }
 }
 
@@ -71,16 +73,79 @@ impl fmt::Display for Vent {
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day5.txt");
-    let vents: Vec<Vent> = INPUT.lines().map(|line| line.parse().unwrap()).collect();
+/// Counts the points where vents overlap, leaving out diagonal vents unless asked.
+pub fn overlaps(vents: &[Vent], diagonals: bool) -> usize {
     let mut map = HashMap::<(u32, u32), u32>::new();
     for vent in vents {
+        if !diagonals && !vent.is_straight() {
+            continue;
+        }
         for (x, y) in vent {
             let counter = map.entry((x, y)).or_insert(0);
             *counter += 1;
         }
     }
-    let solution = map.values().filter(|&count| *count >= 2).count();
-    println!("solution {}", solution);
+    map.values().filter(|&count| *count >= 2).count()
+}
+
+/// Horizontal, vertical and diagonal vents in a 1000 by 1000 area.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let (x1, y1) = (rng.range(0..1000), rng.range(0..1000));
+            let (x2, y2) = match rng.below(3) {
+                0 => (rng.range(0..1000), y1),
+                1 => (x1, rng.range(0..1000)),
+                _ => {
+                    let mut direction = || if rng.chance(1, 2) { 1 } else { -1 };
+                    let (dx, dy) = (direction(), direction());
+                    let room = |p: i64, d: i64| if d > 0 { 999 - p } else { p };
+                    let length = rng.range(0..room(x1, dx).min(room(y1, dy)) + 1);
+                    (x1 + dx * length, y1 + dy * length)
+                }
+            };
+            format!("{},{} -> {},{}\n", x1, y1, x2, y2)
+        })
+        .collect()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<Vent>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const META: Meta = Meta {
+        title: "Hydrothermal Venture",
+        parts: [
+            Part::Solved("the points where horizontal and vertical vents overlap"),
+            Part::Solved("the same with diagonal vents"),
+        ],
+        tests: 1,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse::lines(input)
+    }
+
+    fn part1(vents: &Self::Input) -> usize {
+        overlaps(vents, false)
+    }
+
+    fn part2(vents: &Self::Input) -> usize {
+        overlaps(vents, true)
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    crate::rng::check(100, |rng| {
+        let mut point = || (rng.below(1000) as u32, rng.below(1000) as u32);
+        let vent = Vent {
+            from: point(),
+            to: point(),
+        };
+        assert_eq!(vent.to_string().parse::<Vent>().unwrap(), vent);
+    });
 }
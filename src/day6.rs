@@ -1,23 +1,64 @@
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day6.txt");
-    let fishes: Vec<usize> = INPUT
-        .strip_suffix('\n')
-        .unwrap()
-        .split(',')
-        .map(|timer| timer.parse::<usize>().unwrap())
-        .collect();
-    println!("solution {}", solve(fishes.clone(), 80));
-    println!("solution {}", solve(fishes, 256));
+use crate::meta::{Meta, Part};
+use crate::params::{Param, Tunable};
+use crate::parse::{self, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let timers = (0..size.max(1)).map(|_| rng.below(BIRTH_RATE as u64).to_string());
+    timers.collect::<Vec<String>>().join(",") + "\n"
+}
+
+const PART1_DAYS: Param<usize> = Param::new("part1_days", "80", "days simulated in part 1");
+const PART2_DAYS: Param<usize> = Param::new("part2_days", "256", "days simulated in part 2");
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<usize>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    const META: Meta = Meta {
+        title: "Lanternfish",
+        parts: [
+            Part::Solved("the number of lanternfish after `part1_days` days, 80 by default"),
+            Part::Solved("the same after `part2_days` days, 256 by default"),
+        ],
+        tests: 4,
+    };
+
+    const PARAMS: &'static [&'static dyn Tunable] = &[&PART1_DAYS, &PART2_DAYS];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .trim_end()
+            .split(',')
+            .map(|timer| match parse::number(input, timer)? {
+                timer if timer < BIRTH_RATE => Ok(timer),
+                _ => Err(ParseError::at(input, timer, "a timer below 7")),
+            })
+            .collect()
+    }
+
+    fn part1(fishes: &Self::Input) -> u64 {
+        solve(fishes, PART1_DAYS.get())
+    }
+
+    fn part2(fishes: &Self::Input) -> u64 {
+        solve(fishes, PART2_DAYS.get())
+    }
 }
 
 const BIRTH_RATE: usize = 7;
 const MATURITY: usize = 9;
 
-fn solve(fishes: Vec<usize>, days: usize) -> u64 {
+/// Counts the lanternfish after `days` days, from their initial timers.
+pub fn solve(fishes: &[usize], days: usize) -> u64 {
     let mut timers = [0_u64; BIRTH_RATE];
     let mut new_timers = [0_u64; MATURITY];
 
-    for fish in fishes {
+    for &fish in fishes {
         timers[fish] += 1;
     }
 
@@ -33,23 +74,23 @@ fn solve(fishes: Vec<usize>, days: usize) -> u64 {
 #[test]
 fn test_initial_state() {
     let fishes = vec![3, 4, 3, 1, 2];
-    assert_eq!(solve(fishes, 0), 5);
+    assert_eq!(solve(&fishes, 0), 5);
 }
 
 #[test]
 fn test_18days() {
     let fishes = vec![3, 4, 3, 1, 2];
-    assert_eq!(solve(fishes, 18), 26);
+    assert_eq!(solve(&fishes, 18), 26);
 }
 
 #[test]
 fn test_80days() {
     let fishes = vec![3, 4, 3, 1, 2];
-    assert_eq!(solve(fishes, 80), 5934);
+    assert_eq!(solve(&fishes, 80), 5934);
 }
 
 #[test]
 fn test_256days() {
     let fishes = vec![3, 4, 3, 1, 2];
-    assert_eq!(solve(fishes, 256), 26984457539);
+    assert_eq!(solve(&fishes, 256), 26984457539);
 }
@@ -1,31 +1,70 @@
-fn fuel(crabs: &[u32], position: u32, d: fn(i32, i32) -> i32) -> u32 {
-    crabs
+use crate::checked;
+use crate::meta::{Meta, Part};
+use crate::parse::{Cursor, ParseError};
+use crate::rng::Rng;
+use crate::Solution;
+
+/// The fuel for every crab to reach `position`, where `d` is the cost of a move.
+pub fn fuel(crabs: &[u32], position: u32, d: fn(i32, i32) -> i32) -> u32 {
+    let fuel = crabs
         .iter()
-        .map(|crab| d(*crab as i32, position as i32))
-        .sum::<i32>() as u32
+        .map(|crab| d(checked::cast(*crab), checked::cast(position)))
+        .fold(0, |sum, fuel| checked::add(sum, fuel));
+    checked::cast(fuel)
 }
 
-fn cheapest_fuel(crabs: &[u32], d: fn(i32, i32) -> i32) -> u32 {
+/// The fuel needed to align the crabs on the cheapest position.
+pub fn cheapest_fuel(crabs: &[u32], d: fn(i32, i32) -> i32) -> u32 {
     let position = (0..2000)
         .min_by(|a, b| fuel(crabs, *a, d).cmp(&fuel(crabs, *b, d)))
-        .unwrap() as u32;
+        .unwrap();
     fuel(crabs, position, d)
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day7.txt");
-    let crabs: Vec<u32> = INPUT
-        .strip_suffix('\n')
-        .unwrap()
-        .split(',')
-        .map(|crab| crab.parse().unwrap())
-        .collect();
-    println!("solution {}", cheapest_fuel(&crabs, |a, b| (a - b).abs()));
-    println!(
-        "solution {}",
-        cheapest_fuel(&crabs, |a, b| {
-            let d = (a - b).abs();
-            (d * (d + 1)) / 2
-        })
-    );
+/// Part 1, where each step costs 1 fuel.
+pub fn constant_rate(a: i32, b: i32) -> i32 {
+    (a - b).abs()
+}
+
+/// Part 2, where each step costs 1 more fuel than the one before.
+pub fn increasing_rate(a: i32, b: i32) -> i32 {
+    let d = (a - b).abs();
+    checked::mul(d, checked::add(d, 1)) / 2
+}
+
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let crabs = (0..size.max(1)).map(|_| rng.below(2000).to_string());
+    crabs.collect::<Vec<String>>().join(",") + "\n"
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const META: Meta = Meta {
+        title: "The Treachery of Whales",
+        parts: [
+            Part::Solved("the fuel to align the crabs when each step costs 1"),
+            Part::Solved("the same when each step costs 1 more than the one before"),
+        ],
+        tests: 0,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut cursor = Cursor::new(input, input.trim_end());
+        let crabs = cursor.list(",", Cursor::number)?;
+        cursor.end()?;
+        Ok(crabs)
+    }
+
+    fn part1(crabs: &Self::Input) -> u32 {
+        cheapest_fuel(crabs, constant_rate)
+    }
+
+    fn part2(crabs: &Self::Input) -> u32 {
+        cheapest_fuel(crabs, increasing_rate)
+    }
 }
@@ -1,3 +1,8 @@
+use crate::meta::{Meta, Part};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::Solution;
+
 fn contains(a: &str, b: &str) -> bool {
     b.chars().all(|c| a.contains(c))
 }
@@ -34,7 +39,8 @@ where
         .0
 }
 
-fn decode(patterns: Vec<&str>, outputs: Vec<&str>) -> u32 {
+/// Works out the wiring from the ten patterns and reads the four output digits.
+pub fn decode(patterns: &[&str], outputs: &[&str]) -> u32 {
     let one = patterns.iter().find(|p| p.len() == 2).unwrap();
     let four = patterns.iter().find(|p| p.len() == 4).unwrap();
     let bd = find_bd(one, four);
@@ -56,43 +62,131 @@ fn decode(patterns: Vec<&str>, outputs: Vec<&str>) -> u32 {
         })
         .collect::<Vec<u32>>();
 
-    let mut iter = outputs.into_iter();
-    decode_digit(&patterns, digit_positions.iter(), iter.next().unwrap()) * 1000
-        + decode_digit(&patterns, digit_positions.iter(), iter.next().unwrap()) * 100
-        + decode_digit(&patterns, digit_positions.iter(), iter.next().unwrap()) * 10
-        + decode_digit(&patterns, digit_positions.iter(), iter.next().unwrap())
+    let mut iter = outputs.iter();
+    decode_digit(patterns, digit_positions.iter(), iter.next().unwrap()) * 1000
+        + decode_digit(patterns, digit_positions.iter(), iter.next().unwrap()) * 100
+        + decode_digit(patterns, digit_positions.iter(), iter.next().unwrap()) * 10
+        + decode_digit(patterns, digit_positions.iter(), iter.next().unwrap())
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day8.txt");
-    let easy_digits = INPUT
-        .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .rev()
-                .take(4)
-                .filter(|d| d.len() == 2 || d.len() == 3 || d.len() == 4 || d.len() == 7)
-                .count()
+fn signals(input: &str, s: &str, count: usize) -> Result<Vec<String>, ParseError> {
+    let signals = s
+        .split_ascii_whitespace()
+        .map(|signal| {
+            if !signal.is_empty() && signal.chars().all(|c| ('a'..='g').contains(&c)) {
+                Ok(signal.to_string())
+            } else {
+                Err(ParseError::at(input, signal, "segments a to g"))
+            }
         })
-        .sum::<usize>();
-    println!("solution {}", easy_digits);
-
-    let digits = INPUT
-        .lines()
-        .map(|line| {
-            let (unique_patterns, outputs) = line
-                .split_once(" | ")
-                .map(|(s, t)| {
-                    (
-                        s.split_ascii_whitespace().collect(),
-                        t.split_ascii_whitespace().collect(),
-                    )
-                })
-                .unwrap();
-            decode(unique_patterns, outputs)
+        .collect::<Result<Vec<String>, ParseError>>()?;
+    if signals.len() != count {
+        return Err(ParseError::at(input, s, format!("{} patterns", count)));
+    }
+    Ok(signals)
+}
+
+pub struct Entry {
+    pub patterns: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+impl Entry {
+    pub fn value(&self) -> u32 {
+        decode(
+            &self
+                .patterns
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<&str>>(),
+            &self
+                .outputs
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<&str>>(),
+        )
+    }
+}
+
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// Entries wired with a random permutation of the segments.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let mut wires = "abcdefg".chars().collect::<Vec<char>>();
+            rng.shuffle(&mut wires);
+            let scramble = |digit: &str, rng: &mut Rng| {
+                let mut segments = digit
+                    .bytes()
+                    .map(|segment| wires[(segment - b'a') as usize])
+                    .collect::<Vec<char>>();
+                rng.shuffle(&mut segments);
+                segments.into_iter().collect::<String>()
+            };
+            let mut patterns = DIGITS
+                .iter()
+                .map(|d| scramble(d, rng))
+                .collect::<Vec<String>>();
+            let outputs = (0..4)
+                .map(|_| scramble(DIGITS[rng.index(10)], rng))
+                .collect::<Vec<String>>();
+            rng.shuffle(&mut patterns);
+            format!("{} | {}\n", patterns.join(" "), outputs.join(" "))
         })
-        .sum::<u32>();
-    println!("solution {}", digits);
+        .collect()
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<Entry>;
+    type Part1 = usize;
+    type Part2 = u32;
+
+    const META: Meta = Meta {
+        title: "Seven Segment Search",
+        parts: [
+            Part::Solved("the number of output digits with a unique number of segments"),
+            Part::Solved("the sum of the decoded output values"),
+        ],
+        tests: 2,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let (patterns, outputs) = line
+                    .split_once(" | ")
+                    .ok_or_else(|| ParseError::end(line, "' | '"))
+                    .map_err(|e| e.within(input, line))?;
+                Ok(Entry {
+                    patterns: signals(input, patterns, 10)?,
+                    outputs: signals(input, outputs, 4)?,
+                })
+            })
+            .collect()
+    }
+
+    fn part1(entries: &Self::Input) -> usize {
+        entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .outputs
+                    .iter()
+                    .filter(|d| d.len() == 2 || d.len() == 3 || d.len() == 4 || d.len() == 7)
+                    .count()
+            })
+            .sum()
+    }
+
+    fn part2(entries: &Self::Input) -> u32 {
+        entries.iter().map(Entry::value).sum()
+    }
 }
 
 #[test]
@@ -101,7 +195,7 @@ fn test_decode() {
         "be", "cfbegad", "cbdgef", "fgaecd", "cgeb", "fdcge", "agebfd", "fecdb", "fabcd", "edb",
     ];
     let outputs = vec!["fdgacbe", "cefdb", "cefbgd", "gcbe"];
-    assert_eq!(decode(unique_patterns, outputs), 8394);
+    assert_eq!(decode(&unique_patterns, &outputs), 8394);
 }
 
 #[test]
@@ -1,5 +1,12 @@
 use std::collections::HashSet;
 
+use crate::frames::Color;
+use crate::grid::Grid;
+use crate::meta::{Meta, Part};
+use crate::parse::ParseError;
+use crate::rng::Rng;
+use crate::{Answer, Solution};
+
 fn risk_level(low_point: u32, neighbors: &[u32]) -> u32 {
     if neighbors.iter().all(|p| *p > low_point) {
         low_point + 1
@@ -8,112 +15,154 @@ fn risk_level(low_point: u32, neighbors: &[u32]) -> u32 {
     }
 }
 
-fn main() {
-    const INPUT: &str = include_str!("../inputs/day9.txt");
-    println!("solution: {}", risk(INPUT));
-    println!("solution: {}", largest_basins(INPUT));
+/// Heights from dark to light, then the color of flooded basins.
+pub const PALETTE: [Color; 11] = [
+    [0, 0, 0],
+    [28, 28, 28],
+    [56, 56, 56],
+    [85, 85, 85],
+    [113, 113, 113],
+    [141, 141, 141],
+    [170, 170, 170],
+    [198, 198, 198],
+    [226, 226, 226],
+    [255, 255, 255],
+    [40, 110, 220],
+];
+
+/// The height map, then one more basin flooded in each frame.
+pub fn frames(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError> {
+    let heatmap = Day9::parse(input)?;
+    let mut image = heatmap.map(|height| *height as u8);
+    frame(&image);
+    for basin in basins(&heatmap) {
+        for position in flood_basin(&heatmap, &basin) {
+            image[position] = 10;
+        }
+        frame(&image);
+    }
+    Ok(())
 }
 
-fn neighbors(x: usize, y: usize, row: &[u32], map: &[Vec<u32>]) -> Vec<u32> {
-    let mut neighbors = Vec::new();
-    if x > 0 {
-        neighbors.push(row[x - 1]);
+/// Splits the map into strips with columns of 9, each with a low point, so that
+/// there are at least three basins.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let (width, height) = (size.max(11), size.max(1));
+    let mut heatmap = Grid::new(width, height, 9);
+    for (x, y) in heatmap.positions() {
+        if x % 4 != 3 {
+            heatmap[(x, y)] = if rng.chance(1, 5) {
+                9
+            } else {
+                rng.range(1..9) as u32
+            };
+        }
     }
-    if x < row.len() - 1 {
-        neighbors.push(row[x + 1]);
+    for strip in (0..width).step_by(4) {
+        let x = strip + rng.index(3.min(width - strip));
+        heatmap[(x, rng.index(height))] = 0;
     }
-    if y > 0 {
-        neighbors.push(map[y - 1][x]);
+    heatmap.to_string()
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Grid<u32>;
+    type Part1 = u32;
+    type Part2 = Answer;
+
+    const META: Meta = Meta {
+        title: "Smoke Basin",
+        parts: [
+            Part::Solved("the sum of the risk levels of the low points"),
+            Part::Solved("the product of the sizes of the three largest basins"),
+        ],
+        tests: 2,
+    };
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Grid::parse_digits(input)
     }
-    if y < map.len() - 1 {
-        neighbors.push(map[y + 1][x]);
+
+    fn part1(heatmap: &Self::Input) -> u32 {
+        risk(heatmap)
+    }
+
+    fn part2(heatmap: &Self::Input) -> Answer {
+        match largest_basins(heatmap) {
+            Some(product) => product.into(),
+            None => Answer::Text("fewer than three basins".to_string()),
+        }
     }
-    neighbors
 }
 
-fn risk(input: &str) -> u32 {
-    let heatmap = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect::<Vec<Vec<u32>>>();
+fn neighbors(x: usize, y: usize, map: &Grid<u32>) -> Vec<u32> {
+    map.neighbors4(x, y).map(|p| map[p]).collect()
+}
+
+/// The sum of the risk levels of the low points.
+pub fn risk(heatmap: &Grid<u32>) -> u32 {
     heatmap
-        .iter()
-        .enumerate()
-        .map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .map(|(x, p)| risk_level(*p, &neighbors(x, y, row, &heatmap)))
-                .sum::<u32>()
-        })
+        .positions()
+        .map(|(x, y)| risk_level(heatmap[(x, y)], &neighbors(x, y, heatmap)))
         .sum()
 }
 
-struct Basin {
-    x: usize,
-    y: usize,
-    depth: u32,
+/// The low point at the bottom of a basin.
+pub struct Basin {
+    pub x: usize,
+    pub y: usize,
+    pub depth: u32,
 }
 
 fn floods(from: u32, to: u32) -> bool {
     from != 9 && from > to
 }
 
-fn largest_basins(input: &str) -> u32 {
-    let heatmap = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect::<Vec<Vec<u32>>>();
+pub fn basins(heatmap: &Grid<u32>) -> Vec<Basin> {
     let mut basins = Vec::new();
-    for (y, row) in heatmap.iter().enumerate() {
-        for (x, p) in row.iter().enumerate() {
-            if risk_level(*p, &neighbors(x, y, row, &heatmap)) > 0 {
-                basins.push(Basin { x, y, depth: *p });
-            }
+    for (x, y) in heatmap.positions() {
+        let p = heatmap[(x, y)];
+        if risk_level(p, &neighbors(x, y, heatmap)) > 0 {
+            basins.push(Basin { x, y, depth: p });
         }
     }
-    let mut basin_sizes = basins
-        .iter()
-        .map(|basin| {
-            let mut flood = Vec::new();
-            let mut flooded = HashSet::new();
-            flood.push((basin.x, basin.y, basin.depth));
-            loop {
-                let p = flood.pop();
-                match p {
-                    Some((x, y, height)) if !flooded.contains(&(x, y)) => {
-                        flooded.insert((x, y));
-                        let row = &heatmap[y];
-                        if x > 0 && floods(row[x - 1], height) {
-                            flood.push((x - 1, y, row[x - 1]));
-                        }
-                        if x < row.len() - 1 && floods(row[x + 1], height) {
-                            flood.push((x + 1, y, row[x + 1]));
-                        }
-                        if y > 0 && floods(heatmap[y - 1][x], height) {
-                            flood.push((x, y - 1, heatmap[y - 1][x]));
-                        }
-                        if y < heatmap.len() - 1 && floods(heatmap[y + 1][x], height) {
-                            flood.push((x, y + 1, heatmap[y + 1][x]));
-                        }
+    basins
+}
+
+/// The positions that flow down into a basin.
+pub fn flood_basin(heatmap: &Grid<u32>, basin: &Basin) -> HashSet<(usize, usize)> {
+    let mut flood = Vec::new();
+    let mut flooded = HashSet::new();
+    flood.push((basin.x, basin.y, basin.depth));
+    loop {
+        let p = flood.pop();
+        match p {
+            Some((x, y, height)) if !flooded.contains(&(x, y)) => {
+                flooded.insert((x, y));
+                for (xx, yy) in heatmap.neighbors4(x, y) {
+                    if floods(heatmap[(xx, yy)], height) {
+                        flood.push((xx, yy, heatmap[(xx, yy)]));
                     }
-                    Some(_) => continue,
-                    None => break,
                 }
             }
-            flooded.len() as u32
-        })
+            Some(_) => continue,
+            None => break,
+        }
+    }
+    flooded
+}
+
+/// The product of the sizes of the three largest basins, if there are three.
+pub fn largest_basins(heatmap: &Grid<u32>) -> Option<u32> {
+    let mut basin_sizes = basins(heatmap)
+        .iter()
+        .map(|basin| flood_basin(heatmap, basin).len() as u32)
         .collect::<Vec<u32>>();
 
     basin_sizes.sort_by(|a, b| b.cmp(a));
-    basin_sizes.drain(..3).product()
+    (basin_sizes.len() >= 3).then(|| basin_sizes[..3].iter().product())
 }
 
 #[test]
@@ -131,5 +180,7 @@ fn test_largest_basins() {
 9856789892
 8767896789
 9899965678";
-    assert_eq!(largest_basins(input), 1134);
+    assert_eq!(largest_basins(&Day9::parse(input).unwrap()), Some(1134));
+    assert_eq!(largest_basins(&Day9::parse("191\n").unwrap()), None);
+    assert_eq!(largest_basins(&Day9::parse("").unwrap()), None);
 }
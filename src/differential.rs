@@ -0,0 +1,163 @@
+//! Differential testing of a day's main implementation against a slow but
+//! obviously correct reference one, on small random inputs.
+//!
+//! When they disagree, the input is shrunk by removing lines for as long as
+//! they still disagree, so that the failure reported is a small one.
+
+use std::fmt;
+
+use crate::gen::Generator;
+use crate::rng::Rng;
+use crate::{day11, day12, day20, day22};
+
+/// What comparing the implementations on one input gave.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Agree,
+    Disagree {
+        main: String,
+        reference: String,
+    },
+    /// The input doesn't parse, which happens to shrunk inputs.
+    Invalid,
+}
+
+pub fn compare<T: PartialEq + fmt::Debug>(main: T, reference: T) -> Outcome {
+    if main == reference {
+        Outcome::Agree
+    } else {
+        Outcome::Disagree {
+            main: format!("{:?}", main),
+            reference: format!("{:?}", reference),
+        }
+    }
+}
+
+/// A day's function checked against its reference.
+pub struct Differential {
+    pub day: usize,
+    pub name: &'static str,
+    /// Writes inputs small enough for the reference.
+    pub generate: Generator,
+    pub compare: fn(input: &str) -> Outcome,
+}
+
+pub static DIFFERENTIALS: [Differential; 4] = [
+    Differential {
+        day: 11,
+        name: "step",
+        generate: day11::generate_small,
+        compare: day11::differential,
+    },
+    Differential {
+        day: 12,
+        name: "count_paths",
+        generate: day12::generate,
+        compare: day12::differential,
+    },
+    Differential {
+        day: 20,
+        name: "enhance",
+        generate: day20::generate,
+        compare: day20::differential,
+    },
+    Differential {
+        day: 22,
+        name: "reboot",
+        generate: day22::generate_small,
+        compare: day22::differential,
+    },
+];
+
+/// The smallest input found on which the implementations disagree.
+#[derive(Debug)]
+pub struct Failure {
+    pub day: usize,
+    pub name: &'static str,
+    pub seed: u64,
+    pub input: String,
+    pub main: String,
+    pub reference: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day{}::{} disagrees with its reference on seed {}, shrunk to",
+            self.day, self.name, self.seed
+        )?;
+        write!(f, "{}", self.input)?;
+        writeln!(f, "main:      {}", self.main)?;
+        write!(f, "reference: {}", self.reference)
+    }
+}
+
+/// Compares the implementations on inputs seeded from 0 to `cases`, with
+/// sizes cycling from 1 to `max_size`.
+pub fn check(differential: &Differential, cases: u64, max_size: usize) -> Result<(), Failure> {
+    let disagrees = |input: &str| matches!((differential.compare)(input), Outcome::Disagree { .. });
+    for seed in 0..cases {
+        let size = 1 + seed as usize % max_size;
+        let input = (differential.generate)(size, &mut Rng::new(seed));
+        if disagrees(&input) {
+            let input = shrink(&input, disagrees);
+            let Outcome::Disagree { main, reference } = (differential.compare)(&input) else {
+                unreachable!("shrunk to an input that still disagrees");
+            };
+            return Err(Failure {
+                day: differential.day,
+                name: differential.name,
+                seed,
+                input,
+                main,
+                reference,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Removes chunks of lines from `input`, halving their size down to single
+/// lines, for as long as it still `fails`.
+pub fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let text =
+        |lines: &[&str]| -> String { lines.iter().map(|line| format!("{}\n", line)).collect() };
+    let mut lines = input.lines().collect::<Vec<&str>>();
+    let mut chunk = (lines.len() / 2).max(1);
+    loop {
+        let mut shrunk = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate = [&lines[..start], &lines[end..]].concat();
+            if !candidate.is_empty() && fails(&text(&candidate)) {
+                lines = candidate;
+                shrunk = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if chunk > 1 {
+            chunk /= 2;
+        } else if !shrunk {
+            return text(&lines);
+        }
+    }
+}
+
+#[test]
+fn test_shrink() {
+    let input = (0..20).map(|i| format!("{}\n", i)).collect::<String>();
+    let fails = |input: &str| input.lines().any(|l| l == "3") && input.lines().any(|l| l == "17");
+    assert_eq!(shrink(&input, fails), "3\n17\n");
+}
+
+#[test]
+fn test_differentials() {
+    for differential in &DIFFERENTIALS {
+        if let Err(failure) = check(differential, 200, 6) {
+            panic!("{}", failure);
+        }
+    }
+}
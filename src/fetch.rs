@@ -0,0 +1,271 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies the tool to the puzzle server, as its operator asks automated
+/// clients to do.
+pub const USER_AGENT: &str = "github.com/zoeisnowooze/advent2021 input fetcher";
+
+/// Settings read from a config file of `key = value` lines.
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl FromStr for Config {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::at(s, line, "'key = value'"))?;
+            let (key, value) = (key.trim(), value.trim().to_string());
+            match key {
+                "session" => config.session = Some(value),
+                "base_url" => config.base_url = Some(value),
+                _ => return Err(ParseError::at(s, key, "'session' or 'base_url'")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    Io(io::Error),
+    Url(String),
+    Response(String),
+    Status(u16, String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Io(e) => write!(f, "{}", e),
+            FetchError::Url(url) => {
+                write!(f, "can't fetch '{}', expected an http or https url", url)
+            }
+            FetchError::Response(e) => write!(f, "malformed response: {}", e),
+            FetchError::Status(status @ (400 | 401 | 403), _) => {
+                write!(f, "HTTP {}, the session token was rejected", status)
+            }
+            FetchError::Status(404, _) => write!(f, "HTTP 404, the puzzle isn't unlocked yet"),
+            FetchError::Status(status, body) => {
+                write!(f, "HTTP {}: {}", status, body.lines().next().unwrap_or(""))
+            }
+        }
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::Io(e)
+    }
+}
+
+/// The url of a day's input under a base url.
+pub fn input_url(base_url: &str, day: usize) -> String {
+    format!("{}/2021/day/{}/input", base_url.trim_end_matches('/'), day)
+}
+
+/// Downloads a puzzle input, logged in with `session`.
+///
+/// Plain http is spoken directly, which is enough for a local server, while
+/// https goes through `curl` since the standard library has no TLS.
+pub fn get(url: &str, session: &str) -> Result<String, FetchError> {
+    let (status, body) = if let Some(rest) = url.strip_prefix("http://") {
+        get_http(url, rest, session)?
+    } else if url.starts_with("https://") {
+        get_curl(url, session)?
+    } else {
+        return Err(FetchError::Url(url.to_string()));
+    };
+    match status {
+        200 => Ok(body),
+        _ => Err(FetchError::Status(status, body)),
+    }
+}
+
+fn get_http(url: &str, rest: &str, session: &str) -> Result<(u16, String), FetchError> {
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(FetchError::Url(url.to_string()));
+    }
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let mut stream = TcpStream::connect(address)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\nConnection: close\r\n\r\n",
+        path, authority, USER_AGENT, session
+    )?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    parse_response(&response)
+}
+
+fn get_curl(url: &str, session: &str) -> Result<(u16, String), FetchError> {
+    // The cookie goes through stdin rather than the command line, where other
+    // users could see it.
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--header", "@-"])
+        .args(["--user-agent", USER_AGENT])
+        .args(["--write-out", "\n%{http_code}", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = curl.stdin.take() {
+        writeln!(stdin, "Cookie: session={}", session)?;
+    }
+    let output = curl.wait_with_output()?;
+    if !output.status.success() {
+        let e = String::from_utf8_lossy(&output.stderr);
+        return Err(FetchError::Io(io::Error::other(e.trim())));
+    }
+    let output = String::from_utf8(output.stdout)
+        .map_err(|_| FetchError::Response("the body isn't UTF-8".to_string()))?;
+    let (body, status) = output
+        .rsplit_once('\n')
+        .ok_or_else(|| FetchError::Response("no status from curl".to_string()))?;
+    let status = status
+        .parse()
+        .map_err(|_| FetchError::Response(format!("bad status '{}'", status)))?;
+    Ok((status, body.to_string()))
+}
+
+/// Splits an HTTP/1.1 response into its status code and body, decoding
+/// chunked bodies.
+pub fn parse_response(response: &[u8]) -> Result<(u16, String), FetchError> {
+    let bad = |e: &str| FetchError::Response(e.to_string());
+    let end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| bad("no end of headers"))?;
+    let head = std::str::from_utf8(&response[..end]).map_err(|_| bad("headers aren't UTF-8"))?;
+    let mut body = &response[end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.strip_prefix("HTTP/1."))
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| bad("no status line"))?;
+    let mut chunked = false;
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or_else(|| bad("bad header"))?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            let length = value.parse().map_err(|_| bad("bad content length"))?;
+            body = body.get(..length).ok_or_else(|| bad("body is too short"))?;
+        }
+    }
+
+    let body = if chunked {
+        decode_chunks(body).ok_or_else(|| bad("bad chunk"))?
+    } else {
+        body.to_vec()
+    };
+    let body = String::from_utf8(body).map_err(|_| bad("the body isn't UTF-8"))?;
+    Ok((status, body))
+}
+
+fn decode_chunks(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line = body.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+#[test]
+fn test_parse_config() {
+    let config: Config = "# token\nsession = abc123\nbase_url=http://localhost:8080\n"
+        .parse()
+        .unwrap();
+    assert_eq!(config.session.as_deref(), Some("abc123"));
+    assert_eq!(config.base_url.as_deref(), Some("http://localhost:8080"));
+    let e = "session = a\ntoken = b".parse::<Config>().unwrap_err();
+    assert_eq!((e.line, e.column), (2, 1));
+}
+
+#[test]
+fn test_parse_response() {
+    let response = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n1\n2\nextra";
+    assert_eq!(
+        parse_response(response).unwrap(),
+        (200, "1\n2\n".to_string())
+    );
+    let response = b"HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nNot\r\n6\r\n found\r\n0\r\n\r\n";
+    assert_eq!(
+        parse_response(response).unwrap(),
+        (404, "Not found".to_string())
+    );
+    assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+}
+
+#[test]
+fn test_get() {
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in [
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1\n2\n3\n",
+            "HTTP/1.1 400 Bad Request\r\n\r\nPlease log in.\n",
+        ] {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            requests.push(String::from_utf8(request).unwrap());
+        }
+        requests
+    });
+
+    let url = input_url(&base_url, 1);
+    assert_eq!(get(&url, "abc123").unwrap(), "1\n2\n3\n");
+    let e = get(&url, "expired").unwrap_err();
+    assert_eq!(e.to_string(), "HTTP 400, the session token was rejected");
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /2021/day/1/input HTTP/1.1\r\n"));
+    assert!(requests[0].contains(&format!("User-Agent: {}\r\n", USER_AGENT)));
+    assert!(requests[0].contains("Cookie: session=abc123\r\n"));
+}
@@ -0,0 +1,140 @@
+use std::io::{self, Write};
+
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::{day11, day13, day20, day25, day9};
+
+pub type Color = [u8; 3];
+
+/// Renders each step of a day's simulation as a grid of palette indices.
+pub type Render = fn(input: &str, frame: &mut dyn FnMut(&Grid<u8>)) -> Result<(), ParseError>;
+
+pub struct Animation {
+    pub day: usize,
+    pub palette: &'static [Color],
+    pub render: Render,
+}
+
+pub static ANIMATIONS: [Animation; 5] = [
+    Animation {
+        day: 9,
+        palette: &day9::PALETTE,
+        render: day9::frames,
+    },
+    Animation {
+        day: 11,
+        palette: &day11::PALETTE,
+        render: day11::frames,
+    },
+    Animation {
+        day: 13,
+        palette: &day13::PALETTE,
+        render: day13::frames,
+    },
+    Animation {
+        day: 20,
+        palette: &day20::PALETTE,
+        render: day20::frames,
+    },
+    Animation {
+        day: 25,
+        palette: &day25::PALETTE,
+        render: day25::frames,
+    },
+];
+
+pub fn animation(day: usize) -> Option<&'static Animation> {
+    ANIMATIONS.iter().find(|animation| animation.day == day)
+}
+
+/// Parses comma-separated hex colors such as `000000,ff8800`.
+pub fn parse_palette(s: &str) -> Result<Vec<Color>, ParseError> {
+    s.trim_end()
+        .split(',')
+        .map(|color| {
+            let channel = |i: usize| {
+                color
+                    .get(i..i + 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            };
+            match (color.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok([r, g, b]),
+                _ => Err(ParseError::at(s, color, "a color like ff8800")),
+            }
+        })
+        .collect()
+}
+
+/// Writes a frame as a binary PGM image when the palette is grayscale, or as a
+/// PPM image otherwise, with each cell drawn as a `scale` by `scale` square.
+///
+/// Indices past the end of the palette get its last color.
+pub fn write_image(
+    out: &mut impl Write,
+    frame: &Grid<u8>,
+    palette: &[Color],
+    scale: usize,
+) -> io::Result<()> {
+    let gray = is_gray(palette);
+    let (width, height) = (frame.width() * scale, frame.height() * scale);
+    writeln!(
+        out,
+        "{}\n{} {}\n255",
+        if gray { "P5" } else { "P6" },
+        width,
+        height
+    )?;
+    let mut line = Vec::with_capacity(width * 3);
+    for row in frame.rows() {
+        line.clear();
+        for &index in row {
+            let color = palette[(index as usize).min(palette.len() - 1)];
+            for _ in 0..scale {
+                if gray {
+                    line.push(color[0]);
+                } else {
+                    line.extend(color);
+                }
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_gray(palette: &[Color]) -> bool {
+    palette.iter().all(|[r, g, b]| r == g && g == b)
+}
+
+/// The file extension that [`write_image`] uses for a palette.
+pub fn extension(palette: &[Color]) -> &'static str {
+    if is_gray(palette) {
+        "pgm"
+    } else {
+        "ppm"
+    }
+}
+
+#[test]
+fn test_write_image() {
+    let frame = Grid::from_vec(2, vec![0, 1]);
+    let mut out = Vec::new();
+    write_image(&mut out, &frame, &[[0, 0, 0], [255, 255, 255]], 2).unwrap();
+    assert_eq!(out, b"P5\n4 2\n255\n\0\0\xff\xff\0\0\xff\xff");
+
+    let mut out = Vec::new();
+    write_image(&mut out, &frame, &[[255, 0, 0]], 1).unwrap();
+    assert_eq!(out, b"P6\n2 1\n255\n\xff\0\0\xff\0\0");
+}
+
+#[test]
+fn test_parse_palette() {
+    assert_eq!(
+        parse_palette("000000,Ff8800\n").unwrap(),
+        vec![[0, 0, 0], [255, 136, 0]]
+    );
+    let e = parse_palette("000000,f80").unwrap_err();
+    assert_eq!((e.column, e.text.as_str()), (8, "f80"));
+}
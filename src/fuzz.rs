@@ -0,0 +1,117 @@
+//! Entry points for fuzzers such as cargo-fuzz, which hand over arbitrary bytes.
+//!
+//! A target only returns once the parser has accepted or rejected the input,
+//! and the targets of days 16 and 23 also solve what was accepted, so any
+//! panic is a bug. Day 16 also checks that an accepted packet encodes back to
+//! the same packet. Overflows are reported rather than treated as panics when
+//! built with the `checked-arithmetic` feature.
+
+use std::str;
+
+use crate::checked;
+use crate::parse::ParseError;
+use crate::{day13, day16, day18, day22, day23, Answer, Solution};
+
+pub type Target = fn(data: &[u8]);
+
+pub static TARGETS: [(usize, Target); 5] = [
+    (13, day13),
+    (16, day16),
+    (18, day18),
+    (22, day22),
+    (23, day23),
+];
+
+fn parse<S: Solution>(data: &[u8]) -> Option<Result<S::Input, ParseError>> {
+    str::from_utf8(data).ok().map(S::parse)
+}
+
+/// Parses and solves both parts.
+fn solve<S: Solution>(data: &[u8]) -> Option<[Answer; 2]> {
+    let input = parse::<S>(data)?.ok()?;
+    checked::catch(|| [S::part1(&input).into(), S::part2(&input).into()]).ok()
+}
+
+pub fn day13(data: &[u8]) {
+    parse::<day13::Day13>(data);
+}
+
+pub fn day16(data: &[u8]) {
+    if let Some(Ok(packet)) = parse::<day16::Day16>(data) {
+        let encoded = packet.encode().expect("a parsed packet encodes");
+        assert_eq!(day16::Day16::parse(&encoded), Ok(packet));
+    }
+    solve::<day16::Day16>(data);
+}
+
+pub fn day18(data: &[u8]) {
+    parse::<day18::Day18>(data);
+}
+
+pub fn day22(data: &[u8]) {
+    parse::<day22::Day22>(data);
+}
+
+pub fn day23(data: &[u8]) {
+    solve::<day23::Day23>(data);
+}
+
+#[test]
+fn test_regressions() {
+    // Inputs that used to panic.
+    for input in ["1,1\n\nfold along", "1,1\n\nfold along y=", "1,1\n\nfold"] {
+        assert!(parse::<day13::Day13>(input.as_bytes()).unwrap().is_err());
+    }
+    for input in ["G", "8", "E0", "38006F4529", "EE00D40C82", "FFFFFFFF"] {
+        assert!(parse::<day16::Day16>(input.as_bytes()).unwrap().is_err());
+    }
+    // Operators that used to parse and then panic when evaluated.
+    for input in ["080000", "16004408"] {
+        assert!(solve::<day16::Day16>(input.as_bytes()).is_none());
+    }
+    for input in ["[", "[1,", "[[1,2],3", "[1,2]]", "[10,2]", "[é,1]"] {
+        assert!(parse::<day18::Day18>(input.as_bytes()).unwrap().is_err());
+    }
+    for input in [
+        "on",
+        "of",
+        "on x=1..2",
+        "on x=1..2,y=3..4,z",
+        "on x=2..1,y=0..0,z=0..0",
+    ] {
+        assert!(parse::<day22::Day22>(input.as_bytes()).unwrap().is_err());
+    }
+    // Burrows that used to parse and then panic in the search.
+    for rooms in ["A#A#A#A###\n  #A#A#A#A", ".#.#.#.###\n  #.#.#.#."] {
+        let input = format!("#############\n#...........#\n###{}#\n  #########\n", rooms);
+        assert!(solve::<day23::Day23>(input.as_bytes()).is_none());
+    }
+
+    // Deep nesting, which used to overflow the stack.
+    let packets = "060041801".repeat(100_000);
+    assert!(parse::<day16::Day16>(packets.as_bytes()).unwrap().is_err());
+    let pairs = "[".repeat(100_000) + "1,2" + &"]".repeat(100_000);
+    assert!(parse::<day18::Day18>(pairs.as_bytes()).unwrap().is_err());
+}
+
+#[test]
+fn test_mutated_inputs() {
+    use crate::gen;
+    use crate::rng;
+
+    // Day 23 searches for too long to run here.
+    for (day, target) in TARGETS.into_iter().filter(|(day, _)| *day != 23) {
+        rng::check(300, |rng| {
+            let mut data = gen::generate(day, 3, rng.next_u64()).unwrap().into_bytes();
+            for _ in 0..rng.range(1..4) {
+                let i = rng.index(data.len() + 1);
+                match rng.below(3) {
+                    0 => data.truncate(i),
+                    1 if i < data.len() => data[i] = rng.below(256) as u8,
+                    _ => data.insert(i, *rng.choose(b"0123456789ABCDEF[],.=-\nxyz ")),
+                }
+            }
+            target(&data);
+        });
+    }
+}
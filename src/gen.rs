@@ -0,0 +1,82 @@
+use crate::rng::Rng;
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+
+/// Writes a random input in a day's format, with `size` controlling how big it is.
+pub type Generator = fn(size: usize, rng: &mut Rng) -> String;
+
+pub static GENERATORS: [Generator; 25] = [
+    day1::generate,
+    day2::generate,
+    day3::generate,
+    day4::generate,
+    day5::generate,
+    day6::generate,
+    day7::generate,
+    day8::generate,
+    day9::generate,
+    day10::generate,
+    day11::generate,
+    day12::generate,
+    day13::generate,
+    day14::generate,
+    day15::generate,
+    day16::generate,
+    day17::generate,
+    day18::generate,
+    day19::generate,
+    day20::generate,
+    day21::generate,
+    day22::generate,
+    day23::generate,
+    day24::generate,
+    day25::generate,
+];
+
+/// Generates an input for a day, numbered from 1.
+pub fn generate(day: usize, size: usize, seed: u64) -> Option<String> {
+    let generator = GENERATORS.get(day.checked_sub(1)?)?;
+    Some(generator(size, &mut Rng::new(seed)))
+}
+
+#[test]
+fn test_generated_inputs_parse() {
+    for day in 1..=25 {
+        for seed in 0..3 {
+            let input = generate(day, 10, seed).unwrap();
+            if let Err(e) = crate::DAYS[day - 1].parse(&input) {
+                panic!("day {} seed {}: {}\n{}", day, seed, e, input);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_size_zero_parses() {
+    for day in 1..=25 {
+        let input = generate(day, 0, 0).unwrap();
+        if let Err(e) = crate::DAYS[day - 1].parse(&input) {
+            panic!("day {}: {}\n{}", day, e, input);
+        }
+    }
+}
+
+#[test]
+fn test_generated_inputs_solve() {
+    // Days 23 and 24 search for too long to run here.
+    for day in (1..=22).chain([25]) {
+        let solver = crate::DAYS[day - 1];
+        let input = solver.parse(&generate(day, 10, 2021).unwrap()).unwrap();
+        solver.part1(input.as_ref());
+        solver.part2(input.as_ref());
+    }
+}
+
+#[test]
+fn test_reproducible() {
+    assert_eq!(generate(15, 20, 7), generate(15, 20, 7));
+    assert_ne!(generate(15, 20, 7), generate(15, 20, 8));
+    assert_eq!(generate(26, 20, 7), None);
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::parse::ParseError;
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular map stored row by row, indexed with `(x, y)` positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    wrapping: bool,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_vec(width, vec![value; width * height])
+    }
+
+    /// Lays `cells` out in rows of `width`, which can be 0 for an empty grid.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Grid<T> {
+        assert!(
+            cells.is_empty() || (width > 0 && cells.len().is_multiple_of(width)),
+            "{} cells don't fill rows of {}",
+            cells.len(),
+            width
+        );
+        Grid {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+            wrapping: false,
+        }
+    }
+
+    /// Parses one cell per character, with `cell` rejecting anything but `expected`.
+    pub fn parse(
+        s: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim_end();
+            let mut found = 0;
+            for (j, c) in line.char_indices() {
+                let token = &line[j..j + c.len_utf8()];
+                cells.push(cell(c).ok_or_else(|| ParseError::at(s, token, expected))?);
+                found += 1;
+            }
+            if i == 0 {
+                width = found;
+            } else if found != width {
+                let rest = line
+                    .char_indices()
+                    .nth(width)
+                    .map_or("", |(j, _)| &line[j..]);
+                let token = if found > width {
+                    rest
+                } else {
+                    &line[line.len()..]
+                };
+                return Err(ParseError::at(
+                    s,
+                    token,
+                    format!("{} cells per line", width),
+                ));
+            }
+        }
+        Ok(Grid {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+            wrapping: false,
+        })
+    }
+
+    /// Makes the edges of the grid wrap around to the opposite side.
+    pub fn wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Moves a position by an offset, or returns `None` when it leaves a non-wrapping grid.
+    pub fn offset(
+        &self,
+        position: (usize, usize),
+        offset: (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = position.0 as isize + offset.0;
+        let y = position.1 as isize + offset.1;
+        let (width, height) = (self.width as isize, self.height as isize);
+        if self.wrapping {
+            Some((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize))
+        } else if (0..width).contains(&x) && (0..height).contains(&y) {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The positions above, left, right and below, in that order.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |offset| self.offset((x, y), *offset))
+    }
+
+    /// The surrounding positions, row by row.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ADJACENT
+            .iter()
+            .filter_map(move |offset| self.offset((x, y), *offset))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+            wrapping: self.wrapping,
+        }
+    }
+}
+
+impl Grid<u32> {
+    pub fn parse_digits(s: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(s, "a digit", |c| c.to_digit(10))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) out of bounds",
+            x,
+            y
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) out of bounds",
+            x,
+            y
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse_digits() {
+    let grid = Grid::parse_digits("123\n456\n").unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.row(1), &[4, 5, 6]);
+    assert_eq!(grid.column(1).copied().collect::<Vec<u32>>(), vec![2, 5]);
+    assert_eq!(grid.to_string(), "123\n456\n");
+}
+
+#[test]
+fn test_empty() {
+    let grid = Grid::parse_digits("").unwrap();
+    assert_eq!((grid.width(), grid.height()), (0, 0));
+    assert_eq!(Grid::from_vec(0, Vec::new()), grid);
+    assert_eq!(grid.to_string(), "");
+}
+
+#[test]
+fn test_parse_errors() {
+    let e = Grid::parse_digits("12\n3x\n").unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+    let e = Grid::parse_digits("12\n345\n").unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "5"));
+    let e = Grid::parse_digits("12\n3\n").unwrap_err();
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, ""));
+}
+
+#[test]
+fn test_neighbors() {
+    let grid = Grid::new(3, 3, 0);
+    assert_eq!(
+        grid.neighbors4(0, 0).collect::<Vec<_>>(),
+        vec![(1, 0), (0, 1)]
+    );
+    assert_eq!(grid.neighbors8(1, 1).count(), 8);
+
+    let grid = grid.wrapping();
+    assert_eq!(
+        grid.neighbors4(0, 0).collect::<Vec<_>>(),
+        vec![(0, 2), (2, 0), (1, 0), (0, 1)]
+    );
+}
@@ -0,0 +1,61 @@
+//! Puzzle inputs as the parsers expect them, whatever saved them.
+
+/// The input with `\n` line endings, no whitespace at the end of lines, no
+/// blank lines at the end and a final newline, unless it's empty.
+pub fn normalize(raw: &str) -> String {
+    let mut input = String::with_capacity(raw.len() + 1);
+    for line in raw.split('\n') {
+        input.push_str(line.trim_end());
+        input.push('\n');
+    }
+    let end = input.trim_end().len();
+    input.truncate(end);
+    if !input.is_empty() {
+        input.push('\n');
+    }
+    input
+}
+
+/// Splits a normalized input into the parts separated by one or more blank
+/// lines, as slices of it.
+pub fn sections(input: &str) -> Vec<&str> {
+    input
+        .split("\n\n")
+        .map(|section| section.trim_matches('\n'))
+        .filter(|section| !section.is_empty())
+        .collect()
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("1\r\n2 \r\n\r\n3\t\r\n\r\n\r\n"), "1\n2\n\n3\n");
+    assert_eq!(normalize("1,2,3"), "1,2,3\n");
+    assert_eq!(normalize(" \n\n"), "");
+    assert_eq!(sections(&normalize("a\nb\n\n\n\nc\n")), vec!["a\nb", "c"]);
+}
+
+#[test]
+fn test_mangled_inputs() {
+    use crate::{gen, DAYS};
+
+    for day in 1..=DAYS.len() {
+        let clean = gen::generate(day, 5, day as u64).unwrap();
+        let mangled = clean
+            .trim_end()
+            .replace('\n', " \r\n")
+            .replace(" \r\n \r\n", "\r\n\r\n\r\n");
+        let (clean, mangled) = (DAYS[day - 1].parse(&clean), DAYS[day - 1].parse(&mangled));
+        assert!(mangled.is_ok(), "day {}: {}", day, mangled.err().unwrap());
+        // The days whose parts are quick on any input.
+        if [1, 2, 3, 6, 7, 8, 10, 13, 14, 16, 18].contains(&day) {
+            let (clean, mangled) = (clean.unwrap(), mangled.unwrap());
+            let solver = DAYS[day - 1];
+            assert_eq!(
+                solver.part1(clean.as_ref()),
+                solver.part1(mangled.as_ref()),
+                "day {}",
+                day
+            );
+        }
+    }
+}
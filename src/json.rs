@@ -0,0 +1,98 @@
+use std::fmt::Write;
+
+use crate::alloc::Allocations;
+use crate::Answer;
+
+/// A JSON string literal.
+pub fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// A number, a string, an array of grid rows, or `null` when unsolved.
+pub fn answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Number(n) => n.to_string(),
+        Answer::Text(s) => string(s),
+        Answer::Grid(rows) => {
+            let rows = rows.iter().map(|row| string(row)).collect::<Vec<String>>();
+            format!("[{}]", rows.join(","))
+        }
+        Answer::Unsolved => "null".to_string(),
+    }
+}
+
+/// One part's answer, named intermediate values and, when they were counted,
+/// allocations, as a single-line object.
+pub fn part(
+    day: usize,
+    part: u8,
+    result: &Answer,
+    notes: &[(&str, Answer)],
+    allocations: Option<&Allocations>,
+) -> String {
+    let notes = notes
+        .iter()
+        .map(|(name, value)| format!("{}:{}", string(name), answer(value)))
+        .collect::<Vec<String>>();
+    let allocations = match allocations {
+        Some(a) => format!(
+            ",\"allocations\":{{\"count\":{},\"bytes\":{},\"peak\":{}}}",
+            a.count, a.bytes, a.peak
+        ),
+        None => String::new(),
+    };
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":{},\"intermediates\":{{{}}}{}}}",
+        day,
+        part,
+        answer(result),
+        notes.join(","),
+        allocations
+    )
+}
+
+#[test]
+fn test_string() {
+    assert_eq!(string("a \"b\"\n\\"), "\"a \\\"b\\\"\\n\\\\\"");
+    assert_eq!(string("\u{1}🟪"), "\"\\u0001🟪\"");
+}
+
+#[test]
+fn test_part() {
+    let grid = Answer::Grid(vec!["#.".to_string(), ".#".to_string()]);
+    assert_eq!(
+        part(13, 2, &grid, &[], None),
+        r##"{"day":13,"part":2,"answer":["#.",".#"],"intermediates":{}}"##
+    );
+    let notes = [
+        ("gamma", Answer::Number(22)),
+        ("epsilon", Answer::Number(9)),
+    ];
+    assert_eq!(
+        part(3, 1, &Answer::Number(198), &notes, None),
+        r#"{"day":3,"part":1,"answer":198,"intermediates":{"gamma":22,"epsilon":9}}"#
+    );
+    let allocations = Allocations {
+        count: 2,
+        bytes: 64,
+        peak: 48,
+    };
+    assert_eq!(
+        part(1, 1, &Answer::Number(7), &[], Some(&allocations)),
+        r#"{"day":1,"part":1,"answer":7,"intermediates":{},"allocations":{"count":2,"bytes":64,"peak":48}}"#
+    );
+}
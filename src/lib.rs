@@ -0,0 +1,241 @@
+//! Solvers for the 2021 Advent of Code.
+//!
+//! Each `dayN` module exposes its parsed input types and the functions that
+//! solve it, next to a `DayN` type implementing [`Solution`] on top of them.
+//!
+//! ```
+//! use advent2021::day1::{self, Day1};
+//! use advent2021::day6;
+//! use advent2021::Solution;
+//!
+//! let depths = Day1::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263").unwrap();
+//! assert_eq!(day1::increases(&depths), 7);
+//! assert_eq!(day6::solve(&[3, 4, 3, 1, 2], 80), 5934);
+//! ```
+
+use std::any::Any;
+use std::fmt;
+
+use meta::Meta;
+use params::Tunable;
+use parse::ParseError;
+
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod checked;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod differential;
+pub mod fetch;
+pub mod frames;
+pub mod fuzz;
+pub mod gen;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod meta;
+pub mod notes;
+pub mod params;
+pub mod parse;
+pub mod rng;
+pub mod search;
+pub mod stepper;
+pub mod trace;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    Grid(Vec<String>),
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Number(n as i128)
+            }
+        })*
+    };
+}
+
+number_answer!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Answer::Grid(rows)
+    }
+}
+
+impl From<()> for Answer {
+    fn from(_: ()) -> Self {
+        Answer::Unsolved
+    }
+}
+
+/// A day's puzzle, split into parsing the input and solving each part.
+///
+/// A part that hasn't been solved yet returns `()`.
+pub trait Solution {
+    type Input: 'static;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    const META: Meta;
+
+    /// The puzzle constants that can be changed while running.
+    const PARAMS: &'static [&'static dyn Tunable] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+/// Type-erased [`Solution`], so that all the days can live in one registry.
+///
+/// Inputs are normalized before they are parsed.
+pub trait Solver: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn part1(&self, input: &dyn Any) -> Answer;
+    fn part2(&self, input: &dyn Any) -> Answer;
+    fn params(&self) -> &'static [&'static dyn Tunable];
+    fn meta(&self) -> Meta;
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(&input::normalize(input))?))
+    }
+
+    fn part1(&self, input: &dyn Any) -> Answer {
+        S::part1(input.downcast_ref().expect("input parsed by another day")).into()
+    }
+
+    fn part2(&self, input: &dyn Any) -> Answer {
+        S::part2(input.downcast_ref().expect("input parsed by another day")).into()
+    }
+
+    fn params(&self) -> &'static [&'static dyn Tunable] {
+        S::PARAMS
+    }
+
+    fn meta(&self) -> Meta {
+        S::META
+    }
+}
+
+pub static DAYS: [&dyn Solver; 25] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+/// Looks up the solver for a day, numbered from 1.
+pub fn solver(day: usize) -> Option<&'static dyn Solver> {
+    DAYS.get(day.checked_sub(1)?).copied()
+}
+
+pub fn print_answer(answer: &Answer) {
+    match answer {
+        Answer::Grid(_) => println!("solution\n{}", answer),
+        Answer::Unsolved => {}
+        _ => println!("solution {}", answer),
+    }
+}
+
+/// Parses the input and prints the answers to both parts, with their
+/// allocations when they are counted, or the overflow a part ran into.
+pub fn run(solver: &dyn Solver, input: &str) -> Result<(), ParseError> {
+    let input = match checked::catch(|| solver.parse(input)) {
+        Ok(input) => input?,
+        Err(overflow) => {
+            eprintln!("{}", overflow);
+            return Ok(());
+        }
+    };
+    for part in [1, 2] {
+        let (answer, allocations) = alloc::measure(|| {
+            checked::catch(|| match part {
+                1 => solver.part1(input.as_ref()),
+                _ => solver.part2(input.as_ref()),
+            })
+        });
+        let answer = match answer {
+            Ok(answer) => answer,
+            Err(overflow) => {
+                eprintln!("{}", overflow);
+                continue;
+            }
+        };
+        print_answer(&answer);
+        if alloc::ENABLED && answer != Answer::Unsolved {
+            println!("{}", allocations);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! What each day's puzzle is, and how far along its solver is.
+
+use std::fmt;
+
+/// A day's puzzle, as attached to its [`Solution`](crate::Solution).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Meta {
+    pub title: &'static str,
+    pub parts: [Part; 2],
+    /// The number of unit tests in the day's module.
+    pub tests: usize,
+}
+
+/// One part of a puzzle, with what it computes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    Solved(&'static str),
+    /// Not solved yet, even if the solver returns something for it.
+    Missing(&'static str),
+    /// The second part of the last day, which has no puzzle.
+    NoPuzzle,
+}
+
+impl Part {
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            Part::Solved(description) | Part::Missing(description) => Some(description),
+            Part::NoPuzzle => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Part::Solved(_) => "solved",
+            Part::Missing(_) => "missing",
+            Part::NoPuzzle => "-",
+        })
+    }
+}
+
+#[test]
+fn test_registry() {
+    use crate::DAYS;
+
+    for (i, solver) in DAYS.iter().enumerate() {
+        let meta = solver.meta();
+        assert!(!meta.title.is_empty(), "day {}", i + 1);
+        // Only the last day's second part has no puzzle.
+        let puzzles = meta.parts.iter().filter(|p| **p != Part::NoPuzzle).count();
+        assert_eq!(puzzles, if i == 24 { 1 } else { 2 }, "day {}", i + 1);
+    }
+    // The counts declared by each day match its module.
+    let sources = [
+        include_str!("day1.rs"),
+        include_str!("day2.rs"),
+        include_str!("day3.rs"),
+        include_str!("day4.rs"),
+        include_str!("day5.rs"),
+        include_str!("day6.rs"),
+        include_str!("day7.rs"),
+        include_str!("day8.rs"),
+        include_str!("day9.rs"),
+        include_str!("day10.rs"),
+        include_str!("day11.rs"),
+        include_str!("day12.rs"),
+        include_str!("day13.rs"),
+        include_str!("day14.rs"),
+        include_str!("day15.rs"),
+        include_str!("day16.rs"),
+        include_str!("day17.rs"),
+        include_str!("day18.rs"),
+        include_str!("day19.rs"),
+        include_str!("day20.rs"),
+        include_str!("day21.rs"),
+        include_str!("day22.rs"),
+        include_str!("day23.rs"),
+        include_str!("day24.rs"),
+        include_str!("day25.rs"),
+    ];
+    for (i, source) in sources.iter().enumerate() {
+        let tests = source.lines().filter(|line| *line == "#[test]").count();
+        assert_eq!(DAYS[i].meta().tests, tests, "day {}", i + 1);
+    }
+    assert_eq!(DAYS[0].meta().title, "Sonar Sweep");
+    assert_eq!(
+        DAYS[17].meta().parts[0].description(),
+        Some("the magnitude of the sum of all the numbers")
+    );
+}
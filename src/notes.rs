@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+
+use crate::Answer;
+
+thread_local! {
+    static NOTES: RefCell<Option<Vec<(&'static str, Answer)>>> = const { RefCell::new(None) };
+}
+
+/// Records a named intermediate value of the part being solved, when notes are collected.
+pub fn note(name: &'static str, value: impl Into<Answer>) {
+    NOTES.with(|notes| {
+        if let Some(notes) = notes.borrow_mut().as_mut() {
+            notes.push((name, value.into()));
+        }
+    });
+}
+
+/// Runs `f` and returns its result with the values it noted, in order.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<(&'static str, Answer)>) {
+    let outer = NOTES.with(|notes| notes.replace(Some(Vec::new())));
+    let result = f();
+    let notes = NOTES.with(|notes| notes.replace(outer)).unwrap_or_default();
+    (result, notes)
+}
+
+#[test]
+fn test_collect() {
+    note("ignored", 1);
+    let (answer, notes) = collect(|| {
+        note("gamma", 22);
+        let (_, inner) = collect(|| note("inner", 3));
+        assert_eq!(inner, vec![("inner", Answer::Number(3))]);
+        note("epsilon", 9);
+        198
+    });
+    assert_eq!(answer, 198);
+    assert_eq!(
+        notes,
+        vec![
+            ("gamma", Answer::Number(22)),
+            ("epsilon", Answer::Number(9))
+        ]
+    );
+}
@@ -0,0 +1,171 @@
+//! Puzzle constants that can be changed from the command line, such as the
+//! number of days simulated.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+thread_local! {
+    static OVERRIDES: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The default of a [`Param`] read from the input.
+pub const FROM_INPUT: &str = "from input";
+
+/// A constant of a day, with its default written as it would be given, or
+/// [`FROM_INPUT`] when it isn't a constant.
+pub struct Param<T> {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub help: &'static str,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T> Param<T> {
+    pub const fn new(name: &'static str, default: &'static str, help: &'static str) -> Self {
+        Param {
+            name,
+            default,
+            help,
+            value: PhantomData,
+        }
+    }
+
+    /// A parameter that is read from the input unless it is given.
+    pub const fn from_input(name: &'static str, help: &'static str) -> Self {
+        Param::new(name, FROM_INPUT, help)
+    }
+}
+
+impl<T: FromStr> Param<T> {
+    /// The value given for this parameter while running, if any.
+    pub fn overridden(&self) -> Option<T> {
+        OVERRIDES.with(|overrides| {
+            overrides
+                .borrow()
+                .iter()
+                .rev()
+                .find(|(name, _)| name == self.name)
+                .map(|(_, value)| {
+                    value
+                        .parse()
+                        .unwrap_or_else(|_| panic!("invalid value '{}' for {}", value, self.name))
+                })
+        })
+    }
+
+    /// The value given for this parameter while running, or else the one read
+    /// from the input.
+    pub fn get_or(&self, input: &T) -> T
+    where
+        T: Clone,
+    {
+        self.overridden().unwrap_or_else(|| input.clone())
+    }
+
+    /// The value given for this parameter while running, or else its default.
+    pub fn get(&self) -> T {
+        assert_ne!(
+            self.default, FROM_INPUT,
+            "{} is read from the input",
+            self.name
+        );
+        self.overridden().unwrap_or_else(|| {
+            self.default
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid default for {}", self.name))
+        })
+    }
+}
+
+/// A [`Param`] of any type, so that a day can list them all.
+pub trait Tunable: Sync {
+    fn name(&self) -> &'static str;
+    fn default(&self) -> &'static str;
+    fn help(&self) -> &'static str;
+    /// Checks that a value can be given to the parameter.
+    fn check(&self, value: &str) -> Result<(), String>;
+}
+
+impl<T: FromStr> Tunable for Param<T>
+where
+    T::Err: fmt::Display,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn default(&self) -> &'static str {
+        self.default
+    }
+
+    fn help(&self) -> &'static str {
+        self.help
+    }
+
+    fn check(&self, value: &str) -> Result<(), String> {
+        value
+            .parse::<T>()
+            .map(|_| ())
+            .map_err(|e| format!("invalid value '{}' for {}: {}", value, self.name, e))
+    }
+}
+
+/// Parses a `name=value` pair and checks it against the parameters of a day.
+pub fn parse_override(params: &[&dyn Tunable], s: &str) -> Result<(String, String), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected name=value, got '{}'", s))?;
+    let param = params
+        .iter()
+        .find(|param| param.name() == name)
+        .ok_or_else(|| {
+            let known = params
+                .iter()
+                .map(|param| format!("{} ({})", param.name(), param.default()))
+                .collect::<Vec<String>>();
+            if known.is_empty() {
+                format!("unknown parameter '{}', there are none", name)
+            } else {
+                format!(
+                    "unknown parameter '{}', expected one of {}",
+                    name,
+                    known.join(", ")
+                )
+            }
+        })?;
+    param.check(value)?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Runs `f` with the given values in place of the defaults.
+pub fn with<T>(overrides: &[(String, String)], f: impl FnOnce() -> T) -> T {
+    let outer = OVERRIDES.with(|o| o.replace(overrides.to_vec()));
+    let result = f();
+    OVERRIDES.with(|o| o.replace(outer));
+    result
+}
+
+#[test]
+fn test_overrides() {
+    const DAYS: Param<usize> = Param::new("days", "80", "days simulated");
+    const PARAMS: [&dyn Tunable; 1] = [&DAYS];
+
+    assert_eq!(DAYS.get(), 80);
+    let overrides = [parse_override(&PARAMS, "days=18").unwrap()];
+    assert_eq!(with(&overrides, || DAYS.get()), 18);
+    assert_eq!(DAYS.overridden(), None);
+
+    const START: Param<usize> = Param::from_input("start", "where to start");
+    let overrides = [("start".to_string(), "3".to_string())];
+    assert_eq!(START.get_or(&5), 5);
+    assert_eq!(with(&overrides, || START.get_or(&5)), 3);
+
+    assert!(parse_override(&PARAMS, "days").is_err());
+    assert!(parse_override(&PARAMS, "days=many").is_err());
+    assert_eq!(
+        parse_override(&PARAMS, "weeks=2").unwrap_err(),
+        "unknown parameter 'weeks', expected one of days (80)"
+    );
+}
@@ -0,0 +1,288 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Where and why an input couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+/// Byte offset of `part` in `s`, or the end of `s` if `part` isn't a slice of it.
+fn offset_of(s: &str, part: &str) -> usize {
+    let start = s.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).wrapping_sub(start);
+    if offset <= s.len() && s.is_char_boundary(offset) {
+        offset
+    } else {
+        s.len()
+    }
+}
+
+impl ParseError {
+    /// An error at `token`, which should be a slice of `s`.
+    pub fn at(s: &str, token: &str, expected: impl Into<String>) -> ParseError {
+        let offset = offset_of(s, token);
+        let before = &s[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: token.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error for something missing at the end of `s`.
+    pub fn end(s: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::at(s, &s[s.len()..], expected)
+    }
+
+    /// Moves an error found in `part` to its position in `s`, which contains `part`.
+    pub fn within(self, s: &str, part: &str) -> ParseError {
+        let origin = ParseError::at(s, part, "");
+        ParseError {
+            line: origin.line + self.line - 1,
+            column: if self.line == 1 {
+                origin.column + self.column - 1
+            } else {
+                self.column
+            },
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "'{}'", self.text)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `token`, a slice of `s`, as a number.
+pub fn number<T: FromStr>(s: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::at(s, token, "a number"))
+}
+
+/// Parses every line of `input`, with errors located in `input`.
+pub fn lines<T: FromStr<Err = ParseError>>(input: &str) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| line.parse().map_err(|e: ParseError| e.within(input, line)))
+        .collect()
+}
+
+/// Reads a line from left to right, so that a format is declared as the
+/// pieces it's made of, with errors located in the whole input.
+pub struct Cursor<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+fn is_token(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '+' | '_')
+}
+
+impl<'a> Cursor<'a> {
+    /// A cursor at the start of `s`, which should be a slice of `input`.
+    pub fn new(input: &'a str, s: &'a str) -> Self {
+        Cursor { input, rest: s }
+    }
+
+    /// An error at `token`, a slice of the input.
+    pub fn error(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::at(self.input, token, expected)
+    }
+
+    fn skip_spaces(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t']);
+    }
+
+    /// The next token, or else the next character, to show in errors.
+    fn next(&self) -> &'a str {
+        let rest = self.rest.trim_start_matches([' ', '\t']);
+        match rest.find(|c| !is_token(c)) {
+            Some(0) => &rest[..rest.chars().next().map_or(0, char::len_utf8)],
+            Some(end) => &rest[..end],
+            None => rest,
+        }
+    }
+
+    /// Reads a run of letters, digits, signs and underscores after any spaces.
+    pub fn token(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        self.skip_spaces();
+        let end = self.rest.find(|c| !is_token(c)).unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(self.error(self.next(), expected));
+        }
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(token)
+    }
+
+    /// Reads exactly `literal`.
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                Ok(())
+            }
+            None => {
+                let found = self
+                    .rest
+                    .char_indices()
+                    .nth(literal.chars().count())
+                    .map_or(self.rest, |(end, _)| &self.rest[..end]);
+                Err(self.error(found, format!("'{}'", literal)))
+            }
+        }
+    }
+
+    /// Reads a token that must be one of `choices`, returning its value.
+    pub fn one_of<T: Copy>(
+        &mut self,
+        choices: &[(&str, T)],
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        let token = self.token(expected)?;
+        choices
+            .iter()
+            .find(|(choice, _)| *choice == token)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| self.error(token, expected))
+    }
+
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let token = self.token("a number")?;
+        number(self.input, token)
+    }
+
+    /// Reads two numbers separated by `separator`, such as a point `x,y`.
+    pub fn pair<T: FromStr>(&mut self, separator: &str) -> Result<(T, T), ParseError> {
+        let a = self.number()?;
+        Ok((a, self.number_after(separator)?))
+    }
+
+    /// Reads a range written like `x=10..12`, which can't be empty.
+    pub fn range<T: FromStr + PartialOrd>(
+        &mut self,
+        name: &str,
+    ) -> Result<RangeInclusive<T>, ParseError> {
+        self.skip_spaces();
+        let start = self.rest;
+        self.literal(name)?;
+        self.literal("=")?;
+        let range = self.number()?..=self.number_after("..")?;
+        if range.is_empty() {
+            let written = &start[..start.len() - self.rest.len()];
+            return Err(self.error(written, "an ascending range"));
+        }
+        Ok(range)
+    }
+
+    fn number_after<T: FromStr>(&mut self, literal: &str) -> Result<T, ParseError> {
+        self.literal(literal)?;
+        self.number()
+    }
+
+    /// Reads one or more items separated by `separator`.
+    pub fn list<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while let Some(rest) = self.rest.strip_prefix(separator) {
+            self.rest = rest;
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Checks that only spaces are left.
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_spaces();
+        match self.rest {
+            "" => Ok(()),
+            _ => Err(self.error(self.next(), "the end of the line")),
+        }
+    }
+}
+
+#[test]
+fn test_error_position() {
+    let input = "12\n3x4\n";
+    let e = ParseError::at(input, &input[4..5], "a digit");
+    assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+    assert_eq!(
+        e.to_string(),
+        "line 2, column 2: expected a digit, found 'x'"
+    );
+}
+
+#[test]
+fn test_within() {
+    let input = "1\n2\nthree\n";
+    let line = &input[4..9];
+    let e = number::<u32>(line, line).unwrap_err().within(input, line);
+    assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "three"));
+}
+
+#[test]
+fn test_cursor() {
+    let input = "on x=-5..12,y=3..3\n";
+    let mut cursor = Cursor::new(input, input.trim_end());
+    assert!(cursor
+        .one_of(&[("on", true), ("off", false)], "on or off")
+        .unwrap());
+    let ranges = cursor.list(",", |cursor| {
+        let name = if cursor.next() == "x" { "x" } else { "y" };
+        cursor.range::<i32>(name)
+    });
+    assert_eq!(ranges.unwrap(), vec![-5..=12, 3..=3]);
+    assert!(cursor.end().is_ok());
+
+    let e = |input: &'static str, read: fn(&mut Cursor) -> Result<(), ParseError>| {
+        let e = read(&mut Cursor::new(input, input)).unwrap_err();
+        (e.column, e.text, e.expected)
+    };
+    let expected =
+        |column, text: &str, expected: &str| (column, text.to_string(), expected.to_string());
+    assert_eq!(
+        e("x=2..1", |c| c.range::<u8>("x").map(drop)),
+        expected(1, "x=2..1", "an ascending range")
+    );
+    assert_eq!(
+        e("fold alonk", |c| c.literal("fold along ")),
+        expected(1, "fold alonk", "'fold along '")
+    );
+    assert_eq!(
+        e("7,a", |c| c.list(",", Cursor::number::<u8>).map(drop)),
+        expected(3, "a", "a number")
+    );
+    assert_eq!(
+        e("up 3 ;", |c| {
+            c.token("a word")?;
+            c.number::<u8>()?;
+            c.end()
+        }),
+        expected(6, ";", "the end of the line")
+    );
+}
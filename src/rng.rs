@@ -0,0 +1,87 @@
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A small seeded pseudo-random generator (SplitMix64), so that generated inputs
+/// are reproducible without depending on an external crate.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, which must not be empty.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(range.start < range.end, "empty range");
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add(self.below(span) as i64)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// Checks a property against generators seeded from 0 to `cases`, naming the
+/// seed of the first case that fails so that it can be replayed.
+pub fn check(cases: u64, mut property: impl FnMut(&mut Rng)) {
+    for seed in 0..cases {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| property(&mut Rng::new(seed))));
+        if let Err(e) = result {
+            eprintln!("property failed with seed {}", seed);
+            panic::resume_unwind(e);
+        }
+    }
+}
+
+#[test]
+fn test_reproducible() {
+    let mut a = Rng::new(2021);
+    let mut b = Rng::new(2021);
+    assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+}
+
+#[test]
+fn test_bounds() {
+    let mut rng = Rng::new(7);
+    for _ in 0..1000 {
+        assert!((-5..5).contains(&rng.range(-5..5)));
+        assert!(rng.below(3) < 3);
+    }
+    let mut items = (0..10).collect::<Vec<u32>>();
+    rng.shuffle(&mut items);
+    items.sort_unstable();
+    assert_eq!(items, (0..10).collect::<Vec<u32>>());
+}
@@ -0,0 +1,227 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The cheapest way found to a goal, from the start to the goal included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+/// How much work a search did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States taken off the frontier and expanded.
+    pub expanded: usize,
+    /// States added to the frontier, counting improvements of known states.
+    pub pushed: usize,
+    pub max_frontier: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Search<S, C> {
+    pub route: Option<Route<S, C>>,
+    pub stats: Stats,
+}
+
+/// Every state seen so far, with its best cost and the state it was reached from.
+struct Visited<S, C> {
+    states: Vec<S>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Visited<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Visited {
+            states: vec![start.clone()],
+            costs: vec![cost],
+            parents: vec![None],
+            index: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Records a way to reach `state`, returning its index if it's the best so far.
+    fn reach(&mut self, state: S, cost: C, parent: usize) -> Option<usize> {
+        match self.index.get(&state) {
+            Some(&i) if cost >= self.costs[i] => None,
+            Some(&i) => {
+                self.costs[i] = cost;
+                self.parents[i] = Some(parent);
+                Some(i)
+            }
+            None => {
+                let i = self.states.len();
+                self.index.insert(state.clone(), i);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.parents.push(Some(parent));
+                Some(i)
+            }
+        }
+    }
+
+    fn route(&self, goal: usize) -> Route<S, C> {
+        let mut path = vec![self.states[goal].clone()];
+        let mut i = goal;
+        while let Some(parent) = self.parents[i] {
+            path.push(self.states[parent].clone());
+            i = parent;
+        }
+        path.reverse();
+        Route {
+            cost: self.costs[goal],
+            path,
+        }
+    }
+}
+
+/// Finds the cheapest route with Dijkstra's algorithm.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// Finds the cheapest route with A*, guided by a `heuristic` that must never
+/// overestimate the remaining cost.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut stats = Stats::default();
+    // Lowest estimate first, and on ties the state furthest along.
+    let mut frontier = BinaryHeap::from([(Reverse(heuristic(&start)), C::default(), 0)]);
+    let mut visited = Visited::new(start, C::default());
+
+    while let Some((_, cost, i)) = frontier.pop() {
+        if cost > visited.costs[i] {
+            continue;
+        }
+        stats.expanded += 1;
+        if is_goal(&visited.states[i]) {
+            return Search {
+                route: Some(visited.route(i)),
+                stats,
+            };
+        }
+        for (next, step) in successors(&visited.states[i]) {
+            let estimate = heuristic(&next);
+            if let Some(j) = visited.reach(next, cost + step, i) {
+                frontier.push((Reverse(cost + step + estimate), cost + step, j));
+                stats.pushed += 1;
+            }
+        }
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    Search { route: None, stats }
+}
+
+/// Finds the route with the fewest steps with a breadth-first search.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut stats = Stats::default();
+    let mut frontier = VecDeque::from([0]);
+    let mut visited = Visited::new(start, 0);
+
+    while let Some(i) = frontier.pop_front() {
+        stats.expanded += 1;
+        if is_goal(&visited.states[i]) {
+            return Search {
+                route: Some(visited.route(i)),
+                stats,
+            };
+        }
+        let steps = visited.costs[i] + 1;
+        for next in successors(&visited.states[i]) {
+            if !visited.index.contains_key(&next) {
+                frontier.extend(visited.reach(next, steps, i));
+                stats.pushed += 1;
+            }
+        }
+        stats.max_frontier = stats.max_frontier.max(frontier.len());
+    }
+
+    Search { route: None, stats }
+}
+
+#[cfg(test)]
+const GRAPH: [(char, char, u32); 6] = [
+    ('a', 'b', 7),
+    ('a', 'c', 2),
+    ('c', 'b', 3),
+    ('b', 'd', 1),
+    ('c', 'd', 8),
+    ('d', 'e', 2),
+];
+
+#[cfg(test)]
+fn edges(from: &char) -> Vec<(char, u32)> {
+    GRAPH
+        .iter()
+        .filter(|(a, _, _)| a == from)
+        .map(|(_, b, cost)| (*b, *cost))
+        .collect()
+}
+
+#[test]
+fn test_dijkstra() {
+    let search = dijkstra('a', edges, |state| *state == 'e');
+    let route = search.route.unwrap();
+    assert_eq!(route.cost, 8);
+    assert_eq!(route.path, vec!['a', 'c', 'b', 'd', 'e']);
+    assert_eq!(search.stats.expanded, 5);
+
+    assert_eq!(dijkstra('d', edges, |state| *state == 'a').route, None);
+}
+
+#[test]
+fn test_astar() {
+    // Manhattan distance on an open 10 by 10 square.
+    let successors = |&(x, y): &(i32, i32)| {
+        [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)]
+            .into_iter()
+            .filter(|(x, y)| (0..10).contains(x) && (0..10).contains(y))
+            .map(|position| (position, 1))
+    };
+    let heuristic = |&(x, y): &(i32, i32)| 18 - x - y;
+    let guided = astar((0, 0), successors, heuristic, |p| *p == (9, 9));
+    let blind = dijkstra((0, 0), successors, |p| *p == (9, 9));
+    assert_eq!(guided.route.as_ref().unwrap().cost, 18);
+    assert_eq!(guided.route.unwrap().path.len(), 19);
+    assert_eq!(blind.route.unwrap().cost, 18);
+    assert!(guided.stats.expanded < blind.stats.expanded);
+}
+
+#[test]
+fn test_bfs() {
+    let search = bfs(1, |n| [n + 1, n * 2], |n| *n == 10);
+    let route = search.route.unwrap();
+    assert_eq!(route.cost, 4);
+    assert_eq!(route.path, vec![1, 2, 4, 5, 10]);
+}
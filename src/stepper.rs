@@ -0,0 +1,129 @@
+//! Simulations that can be advanced one step at a time and watched in a
+//! terminal.
+
+use std::fmt::Write;
+
+use crate::parse::ParseError;
+use crate::{day11, day20, day23, day25, input, Answer};
+
+/// A day's simulation, from its input to where it settles.
+pub trait Simulation {
+    /// Advances by one step, or returns false when there's nothing left to do.
+    fn step(&mut self) -> bool;
+    /// Named values describing the state, such as the steps taken so far.
+    fn counters(&self) -> Vec<(&'static str, Answer)>;
+    /// Draws the state as lines of text.
+    fn draw(&self) -> String;
+}
+
+pub type Start = fn(input: &str) -> Result<Box<dyn Simulation>, ParseError>;
+
+pub static SIMULATIONS: [(usize, Start); 4] = [
+    (11, day11::simulation),
+    (20, day20::simulation),
+    (23, day23::simulation),
+    (25, day25::simulation),
+];
+
+/// The day's simulation, if it has one, started from the normalized input.
+pub fn simulation(day: usize) -> Option<impl Fn(&str) -> Result<Box<dyn Simulation>, ParseError>> {
+    SIMULATIONS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, start)| move |input: &str| start(&input::normalize(input)))
+}
+
+pub const HELP: &str = "space: step  <n> enter: run n steps  p: play or pause  q: quit";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    Step(usize),
+    TogglePlay,
+    Quit,
+}
+
+/// Turns key presses into actions, collecting the digits of a step count.
+#[derive(Default)]
+pub struct Keys {
+    count: String,
+}
+
+impl Keys {
+    pub fn press(&mut self, key: u8) -> Option<Action> {
+        match key {
+            b'0'..=b'9' => {
+                self.count.push(key as char);
+                None
+            }
+            b'\n' | b'\r' => {
+                let steps = self.count.parse().unwrap_or(1);
+                self.count.clear();
+                Some(Action::Step(steps))
+            }
+            b' ' | b's' => Some(Action::Step(1)),
+            b'p' => Some(Action::TogglePlay),
+            b'q' | 0x03 | 0x04 => Some(Action::Quit),
+            _ => None,
+        }
+    }
+
+    /// The step count typed so far.
+    pub fn pending(&self) -> &str {
+        &self.count
+    }
+}
+
+/// The whole screen for a state, starting with the escapes that clear the
+/// terminal and move to its top left corner.
+pub fn screen(day: usize, simulation: &dyn Simulation, status: &str) -> String {
+    let mut screen = String::from("\x1b[2J\x1b[H");
+    let counters = simulation
+        .counters()
+        .into_iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<String>>();
+    writeln!(screen, "\x1b[1mday {}\x1b[0m  {}", day, counters.join("  ")).unwrap();
+    writeln!(screen).unwrap();
+    for line in simulation.draw().lines() {
+        writeln!(screen, "{}", line).unwrap();
+    }
+    writeln!(screen).unwrap();
+    writeln!(screen, "\x1b[2m{}\x1b[0m", HELP).unwrap();
+    write!(screen, "{}", status).unwrap();
+    // Raw terminals don't go back to the first column on a line feed.
+    screen.replace('\n', "\r\n")
+}
+
+#[test]
+fn test_keys() {
+    let mut keys = Keys::default();
+    assert_eq!(keys.press(b' '), Some(Action::Step(1)));
+    assert_eq!(keys.press(b'1'), None);
+    assert_eq!(keys.press(b'2'), None);
+    assert_eq!(keys.pending(), "12");
+    assert_eq!(keys.press(b'\n'), Some(Action::Step(12)));
+    assert_eq!(keys.press(b'\r'), Some(Action::Step(1)));
+    assert_eq!(keys.press(b'p'), Some(Action::TogglePlay));
+    assert_eq!(keys.press(b'x'), None);
+    assert_eq!(keys.press(b'q'), Some(Action::Quit));
+}
+
+#[test]
+fn test_simulations() {
+    let mut octopuses = simulation(11).unwrap()("11111\n19991\n19191\n19991\n11111").unwrap();
+    assert!(octopuses.step());
+    assert_eq!(
+        octopuses.counters(),
+        vec![
+            ("step", Answer::Number(1)),
+            ("flashes", Answer::Number(9)),
+            ("total flashes", Answer::Number(9))
+        ]
+    );
+    assert_eq!(octopuses.draw(), "34543\n40004\n50005\n40004\n34543\n");
+
+    let mut cucumbers = simulation(25).unwrap()("...>>>>>...").unwrap();
+    assert!(cucumbers.step());
+    assert_eq!(cucumbers.counters()[1], ("moved", Answer::Number(1)));
+    assert_eq!(cucumbers.draw(), "...>>>>.>..\n");
+}
@@ -0,0 +1,145 @@
+//! Leveled events from inside the solvers, for following a search or a
+//! simulation step by step.
+//!
+//! Events are dropped unless tracing was started with [`init`] at their level
+//! or a more verbose one, and their fields are only formatted when kept.
+
+use std::fmt::{self, Write as _};
+use std::io::{self, BufWriter, Write};
+use std::panic;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    /// A few events for each part, such as a search's result.
+    Info,
+    /// Events for each step of a simulation or candidate of a search.
+    Debug,
+    /// Events for each state expanded or instruction executed.
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Level::Off),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown trace level '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Level::Off => "off",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        })
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Off as u8);
+static SINK: Mutex<Option<BufWriter<Box<dyn Write + Send>>>> = Mutex::new(None);
+
+/// Starts keeping events up to `level`, written to `sink`.
+///
+/// Events are buffered, and written out on [`flush`] or when a thread panics,
+/// so that the ones leading to the panic aren't lost.
+pub fn init(level: Level, sink: Box<dyn Write + Send>) {
+    *SINK.lock().unwrap() = Some(BufWriter::new(sink));
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        flush();
+        hook(info);
+    }));
+}
+
+/// Starts keeping events up to `level`, written to stderr.
+pub fn init_stderr(level: Level) {
+    init(level, Box::new(io::stderr()));
+}
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Writes out the events kept so far.
+pub fn flush() {
+    let mut sink = SINK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(sink) = sink.as_mut() {
+        let _ = sink.flush();
+    }
+}
+
+/// One event on a line: the level, the module it came from, its name and its
+/// fields as `key=value` pairs.
+pub fn format(
+    level: Level,
+    target: &str,
+    name: &str,
+    fields: &[(&str, &dyn fmt::Debug)],
+) -> String {
+    let target = target.rsplit("::").next().unwrap_or(target);
+    let mut line = format!("{:<5} {} {}", level, target, name);
+    for (key, value) in fields {
+        write!(line, " {}={:?}", key, value).unwrap();
+    }
+    line
+}
+
+pub fn write(level: Level, target: &str, name: &str, fields: &[(&str, &dyn fmt::Debug)]) {
+    let line = format(level, target, name, fields);
+    if let Some(sink) = SINK.lock().unwrap().as_mut() {
+        let _ = writeln!(sink, "{}", line);
+    }
+}
+
+/// Emits an event at a level, with a name and `key = value` fields formatted
+/// with `Debug`.
+///
+/// ```
+/// advent2021::event!(Debug, "step", flashes = 12, waves = 3);
+/// ```
+#[macro_export]
+macro_rules! event {
+    ($level:ident, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled($crate::trace::Level::$level) {
+            $crate::trace::write(
+                $crate::trace::Level::$level,
+                module_path!(),
+                $name,
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Debug)),*],
+            );
+        }
+    };
+}
+
+#[test]
+fn test_format() {
+    assert_eq!(
+        format(Level::Info, "advent2021::day15", "found", &[("risk", &40)]),
+        "info  day15 found risk=40"
+    );
+    assert_eq!(
+        format(
+            Level::Trace,
+            "day23",
+            "expanded",
+            &[("energy", &8), ("position", &"A.")]
+        ),
+        "trace day23 expanded energy=8 position=\"A.\""
+    );
+    assert!(Level::Debug < Level::Trace);
+    assert_eq!("debug".parse(), Ok(Level::Debug));
+    assert!("loud".parse::<Level>().is_err());
+}